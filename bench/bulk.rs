@@ -0,0 +1,95 @@
+#![feature(test)]
+
+extern crate bytes;
+extern crate byteorder;
+extern crate test;
+
+use bytes::{BufExt, MutBufExt};
+use byteorder::{BigEndian, LittleEndian};
+use std::io::Cursor;
+use test::Bencher;
+
+const LEN: usize = 4_096;
+
+fn samples() -> Vec<u32> {
+    (0..LEN as u32).map(|i| i.wrapping_mul(0x9E37_79B9)).collect()
+}
+
+fn encoded() -> Vec<u8> {
+    let mut buf = Vec::with_capacity(LEN * 4);
+    buf.write_u32_slice::<BigEndian>(&samples());
+    buf
+}
+
+#[bench]
+pub fn bench_read_u32_loop(b: &mut Bencher) {
+    let src = encoded();
+    let mut dst = vec![0; LEN];
+
+    b.bytes = (LEN * 4) as u64;
+    b.iter(|| {
+        let mut buf = Cursor::new(&src);
+
+        for n in dst.iter_mut() {
+            *n = buf.read_u32::<BigEndian>();
+        }
+
+        test::black_box(&dst);
+    });
+}
+
+#[bench]
+pub fn bench_read_u32_into(b: &mut Bencher) {
+    let src = encoded();
+    let mut dst = vec![0; LEN];
+
+    b.bytes = (LEN * 4) as u64;
+    b.iter(|| {
+        let mut buf = Cursor::new(&src);
+        buf.read_u32_into::<BigEndian>(&mut dst);
+        test::black_box(&dst);
+    });
+}
+
+#[bench]
+pub fn bench_read_u32_into_native(b: &mut Bencher) {
+    let src = encoded();
+    let mut dst = vec![0; LEN];
+
+    b.bytes = (LEN * 4) as u64;
+    b.iter(|| {
+        let mut buf = Cursor::new(&src);
+        buf.read_u32_into::<LittleEndian>(&mut dst);
+        test::black_box(&dst);
+    });
+}
+
+#[bench]
+pub fn bench_write_u32_loop(b: &mut Bencher) {
+    let src = samples();
+    let mut dst = Vec::with_capacity(LEN * 4);
+
+    b.bytes = (LEN * 4) as u64;
+    b.iter(|| {
+        dst.clear();
+
+        for &n in &src {
+            dst.write_u32::<BigEndian>(n);
+        }
+
+        test::black_box(&dst);
+    });
+}
+
+#[bench]
+pub fn bench_write_u32_slice(b: &mut Bencher) {
+    let src = samples();
+    let mut dst = Vec::with_capacity(LEN * 4);
+
+    b.bytes = (LEN * 4) as u64;
+    b.iter(|| {
+        dst.clear();
+        dst.write_u32_slice::<BigEndian>(&src);
+        test::black_box(&dst);
+    });
+}
@@ -1,6 +1,6 @@
 extern crate bytes;
 
-use bytes::{Buf, IntoBuf, Bytes};
+use bytes::{BufExt, IntoBuf, Bytes};
 
 pub fn dump<T>(data: &T) where
     for<'a> &'a T: IntoBuf,
@@ -2,8 +2,6 @@ use alloc::{MemRef};
 use std::sync::Arc;
 
 pub unsafe fn allocate(len: usize) -> MemRef {
-    let mut v = Vec::with_capacity(len);
-    v.set_len(len);
-
+    let v = vec![0; len];
     MemRef::new(Arc::new(v.into_boxed_slice()))
 }
@@ -7,13 +7,11 @@ pub struct Mem {
     mem: Arc<Box<[u8]>>,
 }
 
-pub unsafe fn with_capacity(mut capacity: usize) -> Box<[u8]> {
+pub fn with_capacity(mut capacity: usize) -> Box<[u8]> {
     // Round up to the next power of two
     capacity = capacity.next_power_of_two();
 
-    let mut v: Vec<u8> = Vec::with_capacity(capacity);
-    v.set_len(capacity);
-    v.into_boxed_slice()
+    vec![0; capacity].into_boxed_slice()
 }
 
 impl Mem {
@@ -1,4 +1,6 @@
 use {alloc, MutBuf, Bytes};
+use buf::{Patch, UninitSlice};
+use imp::buf::fill_slices;
 use std::cell::Cell;
 
 /// A `Buf` backed by a contiguous region of memory.
@@ -9,6 +11,7 @@ pub struct AppendBuf {
     mem: alloc::MemRef,
     rd: Cell<u32>, // Read cursor
     wr: u32, // Write cursor
+    shared: Cell<u32>, // End of the bytes referenced by `Bytes` values
     cap: u32,
 }
 
@@ -30,6 +33,7 @@ impl AppendBuf {
             mem: mem,
             rd: Cell::new(pos),
             wr: pos,
+            shared: Cell::new(pos),
             cap: cap,
         }
     }
@@ -73,6 +77,10 @@ impl AppendBuf {
 
         assert!(begin <= end && end <= wr, "invalid range");
 
+        if end > self.shared.get() {
+            self.shared.set(end);
+        }
+
         Bytes::from_boxed(self.mem.get_ref().clone(), begin as usize, (end - begin) as usize)
     }
 }
@@ -99,10 +107,37 @@ impl MutBuf for AppendBuf {
     }
 
     #[inline]
-    unsafe fn mut_bytes(&mut self) -> &mut [u8] {
+    fn mut_bytes(&mut self) -> &mut UninitSlice {
+        let wr = self.wr as usize;
+        let cap = self.cap as usize;
+        unsafe { UninitSlice::new(self.mem.mut_bytes_slice(wr, cap)) }
+    }
+
+    /// The memory is zeroed when it is allocated.
+    fn init_bytes_vec<'a>(&'a mut self, dst: &mut [&'a mut [u8]], max: usize) -> usize {
         let wr = self.wr as usize;
         let cap = self.cap as usize;
-        self.mem.mut_bytes_slice(wr, cap)
+        unsafe { fill_slices(dst, Some(self.mem.mut_bytes_slice(wr, cap)), max) }
+    }
+}
+
+/// Bytes that have been returned by `slice` or `shift` are shared by `Bytes`
+/// values and cannot be patched.
+impl Patch for AppendBuf {
+    fn write_position(&self) -> usize {
+        self.wr as usize
+    }
+
+    fn patch(&mut self, pos: usize, src: &[u8]) {
+        let end = pos + src.len();
+
+        assert!(pos >= self.rd.get() as usize && end <= self.wr as usize,
+                "patch out of bounds");
+        assert!(pos >= self.shared.get() as usize, "patch of shared bytes");
+
+        unsafe {
+            self.mem.mut_bytes_slice(pos, end).copy_from_slice(src);
+        }
     }
 }
 
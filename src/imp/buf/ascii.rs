@@ -0,0 +1,178 @@
+//! Formatting and parsing of ASCII numbers.
+
+use Buf;
+use std::{error, fmt};
+
+/// An error returned when reading an ASCII decimal number from a `Buf`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecimalError {
+    /// The `Buf` did not start with a digit
+    NoDigits,
+    /// The number does not fit in the requested integer type
+    Overflow,
+}
+
+impl fmt::Display for DecimalError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let msg = match *self {
+            DecimalError::NoDigits => "no digits found",
+            DecimalError::Overflow => "number too large to fit in target type",
+        };
+
+        fmt.write_str(msg)
+    }
+}
+
+impl error::Error for DecimalError {
+    fn description(&self) -> &str {
+        match *self {
+            DecimalError::NoDigits => "no digits found",
+            DecimalError::Overflow => "number too large to fit in target type",
+        }
+    }
+}
+
+/// Maximum number of characters needed to format a `u64` in decimal
+pub const MAX_DECIMAL_LEN: usize = 20;
+
+/// Maximum number of characters needed to format a `u64` in hexadecimal
+pub const MAX_HEX_LEN: usize = 16;
+
+/// Maximum number of characters needed to format a float using the shortest
+/// round trip representation, such as `-2.2250738585072014e-308`
+pub const MAX_FLOAT_LEN: usize = 32;
+
+const DEC_DIGITS_LUT: &'static [u8] =
+    b"0001020304050607080910111213141516171819\
+      2021222324252627282930313233343536373839\
+      4041424344454647484950515253545556575859\
+      6061626364656667686970717273747576777879\
+      8081828384858687888990919293949596979899";
+
+const HEX_DIGITS: &'static [u8] = b"0123456789abcdef";
+
+/// Format `n` in decimal into the end of `buf`, returning the offset of the
+/// first digit. `buf` must be at least `MAX_DECIMAL_LEN` bytes long.
+pub fn format_decimal(mut n: u64, buf: &mut [u8]) -> usize {
+    debug_assert!(buf.len() >= MAX_DECIMAL_LEN);
+
+    let mut pos = buf.len();
+
+    // Emit two digits at a time
+    while n >= 100 {
+        let d = ((n % 100) * 2) as usize;
+        n /= 100;
+
+        pos -= 2;
+        buf[pos] = DEC_DIGITS_LUT[d];
+        buf[pos + 1] = DEC_DIGITS_LUT[d + 1];
+    }
+
+    if n >= 10 {
+        let d = (n * 2) as usize;
+
+        pos -= 2;
+        buf[pos] = DEC_DIGITS_LUT[d];
+        buf[pos + 1] = DEC_DIGITS_LUT[d + 1];
+    } else {
+        pos -= 1;
+        buf[pos] = b'0' + n as u8;
+    }
+
+    pos
+}
+
+/// Format `n` in lowercase hexadecimal into the end of `buf`, returning the
+/// offset of the first digit.
+pub fn format_hex(mut n: u64, buf: &mut [u8; MAX_HEX_LEN]) -> usize {
+    let mut pos = buf.len();
+
+    loop {
+        pos -= 1;
+        buf[pos] = HEX_DIGITS[(n & 0xF) as usize];
+        n >>= 4;
+
+        if n == 0 {
+            return pos;
+        }
+    }
+}
+
+/// A fixed size formatting destination, used to format floats without
+/// allocating.
+pub struct StackBuf {
+    buf: [u8; MAX_FLOAT_LEN],
+    len: usize,
+}
+
+impl StackBuf {
+    pub fn new() -> StackBuf {
+        StackBuf {
+            buf: [0; MAX_FLOAT_LEN],
+            len: 0,
+        }
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        &self.buf[..self.len]
+    }
+}
+
+impl fmt::Write for StackBuf {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len + s.len();
+
+        if end > self.buf.len() {
+            return Err(fmt::Error);
+        }
+
+        self.buf[self.len..end].copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+/// Read the digits at the start of `buf`, stopping at the first non-digit or
+/// when the `Buf` is empty. All digits are consumed, even on overflow.
+pub fn read_decimal<B: Buf + ?Sized>(buf: &mut B) -> Result<u64, DecimalError> {
+    let mut ret: u64 = 0;
+    let mut digits = 0;
+    let mut overflow = false;
+
+    while buf.has_remaining() {
+        let (n, done) = {
+            let src = buf.bytes();
+            let mut n = 0;
+
+            for &byte in src {
+                if byte < b'0' || byte > b'9' {
+                    break;
+                }
+
+                match ret.checked_mul(10).and_then(|v| v.checked_add((byte - b'0') as u64)) {
+                    Some(v) => ret = v,
+                    None => overflow = true,
+                }
+
+                n += 1;
+            }
+
+            (n, n < src.len())
+        };
+
+        buf.advance(n);
+        digits += n;
+
+        if done {
+            break;
+        }
+    }
+
+    if digits == 0 {
+        Err(DecimalError::NoDigits)
+    } else if overflow {
+        Err(DecimalError::Overflow)
+    } else {
+        Ok(ret)
+    }
+}
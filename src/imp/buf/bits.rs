@@ -0,0 +1,388 @@
+//! Reading and writing of individual bits.
+
+use {Buf, MutBuf};
+use std::convert::TryFrom;
+
+/// The order in which the bits of each byte are read or written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BitOrder {
+    /// The most significant bit of each byte comes first, as used by H.264
+    /// and most network protocols
+    MsbFirst,
+    /// The least significant bit of each byte comes first, as used by
+    /// DEFLATE
+    LsbFirst,
+}
+
+/// Reads values of arbitrary bit widths from a `Buf`.
+///
+/// Values may straddle byte and chunk boundaries. Multi-bit values are read
+/// most significant bit first for `MsbFirst` and least significant bit first
+/// for `LsbFirst`. Exp-Golomb codes are always read as a sequence of bits
+/// starting with the most significant bit of the code, as with the Huffman
+/// codes of DEFLATE.
+///
+/// ```
+/// use bytes::buf::{BitReader, BitOrder};
+/// use std::io::Cursor;
+///
+/// let mut bits = BitReader::new(Cursor::new(b"\xA5\xF0"), BitOrder::MsbFirst);
+///
+/// assert_eq!(0b1010, bits.read_bits(4));
+/// assert!(!bits.read_bool());
+/// assert_eq!(0b10_1111, bits.read_bits(6));
+/// assert_eq!(5, bits.align_to_byte());
+/// assert_eq!(0, bits.bits_remaining());
+/// ```
+#[derive(Debug)]
+pub struct BitReader<B> {
+    inner: B,
+    order: BitOrder,
+    // Bits read from `inner` but not returned yet, in the low `nbits` bits
+    cache: u64,
+    nbits: u32,
+}
+
+/// Writes values of arbitrary bit widths to a `MutBuf`.
+///
+/// Bytes are written to the `MutBuf` as soon as they are complete. Call
+/// `align_to_byte` or `finish` to write out a final partial byte, padded with
+/// zeros.
+///
+/// ```
+/// use bytes::buf::{BitWriter, BitOrder};
+///
+/// let mut bits = BitWriter::new(vec![], BitOrder::MsbFirst);
+///
+/// bits.write_bits(0b1010, 4);
+/// bits.write_bool(false);
+/// bits.write_bits(0b10_1111, 6);
+///
+/// assert_eq!(bits.finish(), b"\xA5\xE0");
+/// ```
+#[derive(Debug)]
+pub struct BitWriter<B> {
+    inner: B,
+    order: BitOrder,
+    // Bits not written to `inner` yet, in the low `nbits` bits
+    cache: u64,
+    nbits: u32,
+}
+
+/// Returns a mask of the low `n` bits
+fn mask(n: u32) -> u64 {
+    if n >= 64 {
+        !0
+    } else {
+        (1 << n) - 1
+    }
+}
+
+/// Reverse the order of the low `n` bits of `val`
+fn reverse(val: u64, n: u32) -> u64 {
+    if n == 0 {
+        0
+    } else {
+        val.reverse_bits() >> (64 - n)
+    }
+}
+
+/// The maximum number of leading zeros in an Exp-Golomb code whose value
+/// fits in a `u64`
+const MAX_EXP_GOLOMB_ZEROS: u32 = 63;
+
+impl<B: Buf> BitReader<B> {
+    /// Create a new `BitReader` reading from `inner`
+    pub fn new(inner: B, order: BitOrder) -> BitReader<B> {
+        BitReader {
+            inner: inner,
+            order: order,
+            cache: 0,
+            nbits: 0,
+        }
+    }
+
+    /// Returns the bit order
+    pub fn order(&self) -> BitOrder {
+        self.order
+    }
+
+    /// Returns the number of bits that can be read
+    pub fn bits_remaining(&self) -> u64 {
+        self.nbits as u64 + self.inner.remaining() as u64 * 8
+    }
+
+    /// Returns true if the next bit to be read is the first bit of a byte
+    pub fn is_aligned(&self) -> bool {
+        self.nbits == 0
+    }
+
+    /// Reads an `n` bit unsigned integer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n > 64` or if fewer than `n` bits remain.
+    pub fn read_bits(&mut self, n: u32) -> u64 {
+        assert!(n <= 64, "cannot read more than 64 bits at a time");
+        assert!(n as u64 <= self.bits_remaining(), "buffer underflow");
+
+        if n <= 32 {
+            return self.take(n);
+        }
+
+        match self.order {
+            BitOrder::MsbFirst => {
+                let hi = self.take(n - 32);
+                let lo = self.take(32);
+                (hi << 32) | lo
+            }
+            BitOrder::LsbFirst => {
+                let lo = self.take(32);
+                let hi = self.take(n - 32);
+                (hi << 32) | lo
+            }
+        }
+    }
+
+    /// Reads a single bit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no bits remain.
+    pub fn read_bool(&mut self) -> bool {
+        self.read_bits(1) == 1
+    }
+
+    /// Reads an unsigned Exp-Golomb code, `ue(v)` in H.264.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the code is truncated or if its value does not fit in a
+    /// `u64`.
+    pub fn read_exp_golomb(&mut self) -> u64 {
+        let mut zeros = 0;
+
+        while !self.read_bool() {
+            zeros += 1;
+            assert!(zeros <= MAX_EXP_GOLOMB_ZEROS, "Exp-Golomb code too long");
+        }
+
+        let rest = self.read_bits(zeros);
+        mask(zeros) + self.code_bits(rest, zeros)
+    }
+
+    /// Reads a signed Exp-Golomb code, `se(v)` in H.264.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the code is truncated or if its value does not fit in an
+    /// `i64`.
+    pub fn read_signed_exp_golomb(&mut self) -> i64 {
+        let k = self.read_exp_golomb();
+
+        // Odd codes map to positive values and even codes to negative ones
+        let abs = i64::try_from(k / 2 + (k & 1))
+            .expect("Exp-Golomb value does not fit in an i64");
+
+        if k & 1 == 1 { abs } else { -abs }
+    }
+
+    /// Skip to the start of the next byte, returning the number of bits
+    /// skipped.
+    pub fn align_to_byte(&mut self) -> u32 {
+        let n = self.nbits;
+        self.cache = 0;
+        self.nbits = 0;
+        n
+    }
+
+    pub fn get_ref(&self) -> &B {
+        &self.inner
+    }
+
+    /// Returns the underlying `Buf`. Unread bits of a partially read byte are
+    /// discarded.
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+
+    /// Convert `n` bits of a code, which are sent most significant bit first
+    /// regardless of the bit order, to or from the order of `read_bits`
+    fn code_bits(&self, val: u64, n: u32) -> u64 {
+        match self.order {
+            BitOrder::MsbFirst => val,
+            BitOrder::LsbFirst => reverse(val, n),
+        }
+    }
+
+    /// Take `n <= 32` bits, which must be available
+    fn take(&mut self, n: u32) -> u64 {
+        // Pull in whole bytes, leaving fewer than 8 bits in the cache once
+        // `n` bits have been taken
+        while self.nbits < n {
+            let byte = self.inner.read_u8() as u64;
+
+            match self.order {
+                BitOrder::MsbFirst => self.cache = (self.cache << 8) | byte,
+                BitOrder::LsbFirst => self.cache |= byte << self.nbits,
+            }
+
+            self.nbits += 8;
+        }
+
+        self.nbits -= n;
+
+        match self.order {
+            BitOrder::MsbFirst => {
+                let ret = self.cache >> self.nbits;
+                self.cache &= mask(self.nbits);
+                ret
+            }
+            BitOrder::LsbFirst => {
+                let ret = self.cache & mask(n);
+                self.cache >>= n;
+                ret
+            }
+        }
+    }
+}
+
+impl<B: MutBuf> BitWriter<B> {
+    /// Create a new `BitWriter` writing to `inner`
+    pub fn new(inner: B, order: BitOrder) -> BitWriter<B> {
+        BitWriter {
+            inner: inner,
+            order: order,
+            cache: 0,
+            nbits: 0,
+        }
+    }
+
+    /// Returns the bit order
+    pub fn order(&self) -> BitOrder {
+        self.order
+    }
+
+    /// Returns true if the next bit to be written is the first bit of a byte
+    pub fn is_aligned(&self) -> bool {
+        self.nbits == 0
+    }
+
+    /// Writes the low `n` bits of `val`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n > 64`, if `val` does not fit in `n` bits or if there is
+    /// not enough remaining capacity.
+    pub fn write_bits(&mut self, val: u64, n: u32) {
+        assert!(n <= 64, "cannot write more than 64 bits at a time");
+        assert!(val & !mask(n) == 0, "{} does not fit in {} bits", val, n);
+
+        if n <= 32 {
+            return self.put(val, n);
+        }
+
+        match self.order {
+            BitOrder::MsbFirst => {
+                self.put(val >> 32, n - 32);
+                self.put(val & mask(32), 32);
+            }
+            BitOrder::LsbFirst => {
+                self.put(val & mask(32), 32);
+                self.put(val >> 32, n - 32);
+            }
+        }
+    }
+
+    /// Writes a single bit.
+    pub fn write_bool(&mut self, val: bool) {
+        self.put(val as u64, 1)
+    }
+
+    /// Writes an unsigned Exp-Golomb code, `ue(v)` in H.264.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `val` is `u64::MAX`, which cannot be represented.
+    pub fn write_exp_golomb(&mut self, val: u64) {
+        let val = val.checked_add(1).expect("value too large for an Exp-Golomb code");
+        let len = 64 - val.leading_zeros();
+
+        self.write_bits(0, len - 1);
+
+        let val = self.code_bits(val, len);
+        self.write_bits(val, len);
+    }
+
+    /// Writes a signed Exp-Golomb code, `se(v)` in H.264.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `val` is `i64::MIN`, which cannot be represented.
+    pub fn write_signed_exp_golomb(&mut self, val: i64) {
+        let k = if val > 0 {
+            (val as u64) * 2 - 1
+        } else {
+            let abs = val.checked_neg().expect("value too large for an Exp-Golomb code");
+            (abs as u64) * 2
+        };
+
+        self.write_exp_golomb(k)
+    }
+
+    /// Pad with zero bits to the start of the next byte, writing out the
+    /// current partial byte. Returns the number of bits of padding.
+    pub fn align_to_byte(&mut self) -> u32 {
+        if self.nbits == 0 {
+            return 0;
+        }
+
+        let pad = 8 - self.nbits;
+        self.put(0, pad);
+        pad
+    }
+
+    pub fn get_ref(&self) -> &B {
+        &self.inner
+    }
+
+    /// Write out any partial byte, padded with zero bits, and return the
+    /// underlying `MutBuf`.
+    pub fn finish(mut self) -> B {
+        self.align_to_byte();
+        self.inner
+    }
+
+    /// See `BitReader::code_bits`
+    fn code_bits(&self, val: u64, n: u32) -> u64 {
+        match self.order {
+            BitOrder::MsbFirst => val,
+            BitOrder::LsbFirst => reverse(val, n),
+        }
+    }
+
+    /// Put `n <= 32` bits
+    fn put(&mut self, val: u64, n: u32) {
+        match self.order {
+            BitOrder::MsbFirst => self.cache = (self.cache << n) | val,
+            BitOrder::LsbFirst => self.cache |= val << self.nbits,
+        }
+
+        self.nbits += n;
+
+        while self.nbits >= 8 {
+            self.nbits -= 8;
+
+            match self.order {
+                BitOrder::MsbFirst => {
+                    self.inner.write_u8((self.cache >> self.nbits) as u8);
+                    self.cache &= mask(self.nbits);
+                }
+                BitOrder::LsbFirst => {
+                    self.inner.write_u8(self.cache as u8);
+                    self.cache >>= 8;
+                }
+            }
+        }
+    }
+}
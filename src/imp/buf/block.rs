@@ -1,8 +1,8 @@
 #![allow(warnings)]
 
 use {alloc, Buf, MutBuf, Bytes};
-use buf::AppendBuf;
-use std::{cmp, ptr, slice};
+use buf::{AppendBuf, Checkpoint, SeekBuf, UninitSlice};
+use std::cmp;
 use std::io::Cursor;
 use std::rc::Rc;
 use std::collections::{vec_deque, VecDeque};
@@ -16,6 +16,7 @@ pub struct BlockBuf {
     len: usize,
     cap: usize,
     blocks: VecDeque<AppendBuf>,
+    max_blocks: usize,
     new_block: NewBlock,
 }
 
@@ -25,8 +26,12 @@ enum NewBlock {
 }
 
 pub struct BlockBufCursor<'a> {
+    // All of the blocks, used to seek
+    all: &'a VecDeque<AppendBuf>,
+    len: usize,
     rem: usize,
     blocks: vec_deque::Iter<'a, AppendBuf>,
+    block: Option<&'a AppendBuf>,
     curr: Option<Cursor<&'a [u8]>>,
 }
 
@@ -46,6 +51,7 @@ impl BlockBuf {
             len: 0,
             cap: max_blocks * new_block.block_size(),
             blocks: VecDeque::with_capacity(max_blocks),
+            max_blocks: max_blocks,
             new_block: new_block,
         }
     }
@@ -69,13 +75,16 @@ impl BlockBuf {
         let mut iter = self.blocks.iter();
 
         // Get the next leaf node buffer
-        let block = iter.next()
-            .map(|block| Cursor::new(block.bytes()));
+        let block = iter.next();
+        let curr = block.map(|block| Cursor::new(block.bytes()));
 
         BlockBufCursor {
+            all: &self.blocks,
+            len: self.len(),
             rem: self.len(),
             blocks: iter,
-            curr: block,
+            block: block,
+            curr: curr,
         }
     }
 
@@ -227,7 +236,7 @@ impl BlockBuf {
     #[inline]
     pub fn bytes(&self) -> Option<&[u8]> {
         match self.blocks.len() {
-            0 => Some(unsafe { slice::from_raw_parts(ptr::null(), 0) }),
+            0 => Some(&[]),
             1 => self.blocks.front().map(|b| b.bytes()),
             _ => None,
         }
@@ -251,17 +260,35 @@ impl BlockBuf {
         self.len() > 0
     }
 
+    /// Returns the index of the first block that can be written to, or the
+    /// number of blocks if they are all full. Blocks following it are empty,
+    /// as `mut_bytes_vec` may allocate several blocks ahead of writes.
     #[inline]
-    fn needs_alloc(&self) -> bool {
-        if let Some(buf) = self.blocks.back() {
-            // `unallocated_blocks` is checked here because if further blocks
-            // cannot be allocated, an empty slice should be returned.
-            if MutBuf::has_remaining(buf) {
-                return false;
+    fn write_block(&self) -> usize {
+        let mut i = self.blocks.len();
+
+        while i > 0 && MutBuf::has_remaining(&self.blocks[i - 1]) {
+            i -= 1;
+        }
+
+        i
+    }
+
+    /// Allocates blocks so that `cnt` blocks can be written to, up to the
+    /// maximum number of blocks, and returns the index of the first one.
+    fn allocate_ahead(&mut self, cnt: usize) -> usize {
+        let first = self.write_block();
+
+        while self.blocks.len() - first < cnt && self.blocks.len() < self.max_blocks {
+            let len = self.blocks.len();
+            self.allocate_block();
+
+            if self.blocks.len() == len {
+                break;
             }
         }
 
-        true
+        first
     }
 }
 
@@ -280,28 +307,98 @@ impl MutBuf for BlockBuf {
         self.cap != self.len
     }
 
-    unsafe fn advance(&mut self, cnt: usize) {
+    unsafe fn advance(&mut self, mut cnt: usize) {
         trace!("BlockBuf::advance; cnt={:?}", cnt);
 
-        // `mut_bytes` only returns bytes from the last block, thus it should
-        // only be possible to advance the last block
-        if let Some(buf) = self.blocks.back_mut() {
-            self.len += cnt;
-            buf.advance(cnt);
+        // Bytes written through `mut_bytes_vec` may span several blocks, so
+        // advance each block in turn
+        let first = self.write_block();
+
+        for buf in self.blocks.iter_mut().skip(first) {
+            let n = cmp::min(cnt, MutBuf::remaining(buf));
+
+            self.len += n;
+            buf.advance(n);
+            cnt -= n;
+
+            if cnt == 0 {
+                break;
+            }
         }
+
+        assert!(cnt == 0, "buffer overflow");
+
+        self.release_unused();
     }
 
     #[inline]
-    unsafe fn mut_bytes<'a>(&'a mut self) -> &'a mut [u8] {
-        if self.needs_alloc() {
-            if self.blocks.len() != self.blocks.capacity() {
-                self.allocate_block()
+    fn mut_bytes(&mut self) -> &mut UninitSlice {
+        let i = self.write_block();
+
+        if i == self.blocks.len() && self.blocks.len() < self.max_blocks {
+            self.allocate_block()
+        }
+
+        match self.blocks.get_mut(i) {
+            Some(buf) => buf.mut_bytes(),
+            None => UninitSlice::new(&mut []),
+        }
+    }
+
+    /// Allocates blocks ahead of writes, up to the maximum number of blocks,
+    /// so that there is a slice for each entry of `dst`. Blocks that are not
+    /// written to are released by the next call to `advance` or
+    /// `release_unused`.
+    fn mut_bytes_vec<'a>(&'a mut self, dst: &mut [&'a mut UninitSlice]) -> usize {
+        let first = self.allocate_ahead(dst.len());
+        let mut rem = MutBuf::remaining(self);
+        let mut n = 0;
+
+        for buf in self.blocks.iter_mut().skip(first) {
+            if n == dst.len() || rem == 0 {
+                break;
+            }
+
+            let bytes = buf.mut_bytes();
+            let len = cmp::min(bytes.len(), rem);
+
+            rem -= len;
+            dst[n] = &mut bytes[..len];
+            n += 1;
+        }
+
+        n
+    }
+
+    /// Blocks are zeroed when they are allocated, so their memory is returned
+    /// as is.
+    fn init_bytes_vec<'a>(&'a mut self, dst: &mut [&'a mut [u8]], max: usize) -> usize {
+        let first = self.allocate_ahead(dst.len());
+        let mut rem = cmp::min(MutBuf::remaining(self), max);
+        let mut n = 0;
+
+        for buf in self.blocks.iter_mut().skip(first) {
+            if n == dst.len() || rem == 0 {
+                break;
+            }
+
+            if buf.init_bytes_vec(&mut dst[n..n + 1], rem) == 1 {
+                rem -= dst[n].len();
+                n += 1;
             }
         }
 
-        self.blocks.back_mut()
-            .map(|buf| buf.mut_bytes())
-            .unwrap_or(slice::from_raw_parts_mut(ptr::null_mut(), 0))
+        n
+    }
+
+    /// Releases the trailing blocks that were allocated ahead of writes but
+    /// that were never written to.
+    fn release_unused(&mut self) {
+        let block_size = self.block_size();
+
+        while self.blocks.back().map(|b| b.len() == 0 && b.capacity() == block_size).unwrap_or(false) {
+            self.blocks.pop_back();
+        }
     }
 }
 
@@ -319,7 +416,7 @@ impl<'a> Buf for BlockBufCursor<'a> {
     fn bytes(&self) -> &[u8] {
         self.curr.as_ref()
             .map(|buf| Buf::bytes(buf))
-            .unwrap_or(unsafe { slice::from_raw_parts(ptr::null(), 0)})
+            .unwrap_or(&[])
     }
 
     fn advance(&mut self, mut cnt: usize) {
@@ -342,12 +439,83 @@ impl<'a> Buf for BlockBufCursor<'a> {
                 cnt -= curr.remaining();
             }
 
-            self.curr = self.blocks.next()
-                .map(|block| Cursor::new(block.bytes()));
+            self.block = self.blocks.next();
+            self.curr = self.block.map(|block| Cursor::new(block.bytes()));
+        }
+    }
+
+    fn peek_slice(&self, dst: &mut [u8]) -> usize {
+        let mut n = cmp::min(dst.len(), self.rem);
+        let mut off = 0;
+        let rest = self.blocks.clone().map(|block| block.bytes());
+
+        for src in Some(self.bytes()).into_iter().chain(rest) {
+            let len = cmp::min(src.len(), n);
+
+            dst[off..off + len].copy_from_slice(&src[..len]);
+            off += len;
+            n -= len;
+
+            if n == 0 {
+                break;
+            }
+        }
+
+        off
+    }
+
+    fn bytes_shared(&self) -> Option<Bytes> {
+        match (self.block, self.curr.as_ref()) {
+            (Some(block), Some(curr)) => {
+                Some(block.slice(curr.position() as usize, block.len()))
+            }
+            _ => Some(Bytes::empty()),
         }
     }
 }
 
+impl<'a> SeekBuf for BlockBufCursor<'a> {
+    fn position(&self) -> usize {
+        self.len - self.rem
+    }
+
+    /// Seeking walks the blocks from the start, which is O(blocks).
+    fn set_position(&mut self, mut pos: usize) {
+        assert!(pos <= self.len, "position out of bounds");
+
+        self.rem = self.len - pos;
+        self.blocks = self.all.iter();
+        self.block = self.blocks.next();
+
+        while let Some(block) = self.block {
+            if pos < block.len() {
+                break;
+            }
+
+            pos -= block.len();
+            self.block = self.blocks.next();
+        }
+
+        self.curr = self.block.map(|block| {
+            let mut curr = Cursor::new(block.bytes());
+            curr.set_position(pos as u64);
+            curr
+        });
+    }
+}
+
+impl<'a> Checkpoint for BlockBufCursor<'a> {
+    type Mark = usize;
+
+    fn checkpoint(&self) -> usize {
+        self.position()
+    }
+
+    fn rewind(&mut self, mark: usize) {
+        self.set_position(mark)
+    }
+}
+
 impl NewBlock {
     #[inline]
     fn block_size(&self) -> usize {
@@ -0,0 +1,93 @@
+//! Bulk reads and writes of integer slices.
+
+use {Buf, MutBuf};
+use byteorder::ByteOrder;
+use imp::buf::num;
+use std::{mem, slice};
+
+/// Integer types that can be read and written in bulk
+pub trait Word: Copy {
+    fn swap_bytes(self) -> Self;
+}
+
+impl Word for u16 {
+    fn swap_bytes(self) -> u16 { u16::swap_bytes(self) }
+}
+
+impl Word for u32 {
+    fn swap_bytes(self) -> u32 { u32::swap_bytes(self) }
+}
+
+impl Word for u64 {
+    fn swap_bytes(self) -> u64 { u64::swap_bytes(self) }
+}
+
+/// Number of bytes byte-swapped on the stack at a time when writing
+const SWAP_BUF_LEN: usize = 512;
+
+/// Returns true if `T` is the byte order of the target
+fn is_native<T: ByteOrder>() -> bool {
+    num::is_big_endian::<T>() == cfg!(target_endian = "big")
+}
+
+fn as_bytes<N: Word>(src: &[N]) -> &[u8] {
+    unsafe { slice::from_raw_parts(src.as_ptr() as *const u8, src.len() * mem::size_of::<N>()) }
+}
+
+fn as_bytes_mut<N: Word>(dst: &mut [N]) -> &mut [u8] {
+    unsafe { slice::from_raw_parts_mut(dst.as_mut_ptr() as *mut u8, dst.len() * mem::size_of::<N>()) }
+}
+
+/// Fill `dst` with integers read from `buf` in the byte order `T`.
+pub fn read_into<T: ByteOrder, B: Buf + ?Sized, N: Word>(buf: &mut B, dst: &mut [N]) {
+    buf.read_slice(as_bytes_mut(dst));
+
+    if !is_native::<T>() {
+        for n in dst.iter_mut() {
+            *n = n.swap_bytes();
+        }
+    }
+}
+
+/// Write all of the integers in `src` to `buf` in the byte order `T`.
+pub fn write_from<T: ByteOrder, B: MutBuf + ?Sized, N: Word>(buf: &mut B, src: &[N]) {
+    if is_native::<T>() {
+        return buf.write_slice(as_bytes(src));
+    }
+
+    let len = src.len() * mem::size_of::<N>();
+    assert!(buf.remaining() >= len, "buffer overflow");
+
+    let mut tmp = [0u64; SWAP_BUF_LEN / 8];
+    let per_batch = SWAP_BUF_LEN / mem::size_of::<N>();
+
+    for batch in src.chunks(per_batch) {
+        // `tmp` is only used as a properly aligned region of `SWAP_BUF_LEN`
+        // bytes, large enough to hold `per_batch` integers of any `Word` type
+        let swapped = unsafe {
+            slice::from_raw_parts_mut(tmp.as_mut_ptr() as *mut N, batch.len())
+        };
+
+        for (dst, &n) in swapped.iter_mut().zip(batch) {
+            *dst = n.swap_bytes();
+        }
+
+        buf.write_slice(as_bytes(swapped));
+    }
+}
+
+pub fn f32_as_u32(src: &[f32]) -> &[u32] {
+    unsafe { slice::from_raw_parts(src.as_ptr() as *const u32, src.len()) }
+}
+
+pub fn f32_as_u32_mut(dst: &mut [f32]) -> &mut [u32] {
+    unsafe { slice::from_raw_parts_mut(dst.as_mut_ptr() as *mut u32, dst.len()) }
+}
+
+pub fn f64_as_u64(src: &[f64]) -> &[u64] {
+    unsafe { slice::from_raw_parts(src.as_ptr() as *const u64, src.len()) }
+}
+
+pub fn f64_as_u64_mut(dst: &mut [f64]) -> &mut [u64] {
+    unsafe { slice::from_raw_parts_mut(dst.as_mut_ptr() as *mut u64, dst.len()) }
+}
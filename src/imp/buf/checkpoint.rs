@@ -0,0 +1,118 @@
+//! Saving and restoring the read position of a `Buf`.
+
+use Buf;
+use std::ops;
+
+/// A `Buf` whose read position can be saved and later restored.
+///
+/// This supports speculative parsing: take a checkpoint, try to decode a
+/// frame, and rewind if the frame turns out to be incomplete. `guard`
+/// returns a `Rewind` value that does this automatically.
+pub trait Checkpoint: Buf {
+    /// The saved state of the `Buf`
+    type Mark;
+
+    /// Returns the current state of the `Buf`, to be passed to `rewind`
+    fn checkpoint(&self) -> Self::Mark;
+
+    /// Restores the `Buf` to the state it had when `mark` was returned by
+    /// `checkpoint`.
+    ///
+    /// # Panics
+    ///
+    /// May panic if the bytes following `mark` are no longer available, for
+    /// example because they have been overwritten in a `RingBuf`.
+    fn rewind(&mut self, mark: Self::Mark);
+
+    /// Returns a guard that gives access to the `Buf` and rewinds it to the
+    /// current state when dropped, unless `Rewind::commit` is called.
+    ///
+    /// ```
+    /// extern crate bytes;
+    /// extern crate byteorder;
+    ///
+    /// use bytes::{Buf, BufExt};
+    /// use bytes::buf::Checkpoint;
+    /// use byteorder::BigEndian;
+    /// use std::io::Cursor;
+    ///
+    /// /// Decode a length prefixed frame, leaving `buf` untouched if the
+    /// /// frame is incomplete
+    /// fn decode<B: Checkpoint>(buf: &mut B) -> Option<Vec<u8>> {
+    ///     let mut buf = buf.guard();
+    ///
+    ///     if buf.remaining() < 2 {
+    ///         return None;
+    ///     }
+    ///
+    ///     let len = buf.read_u16::<BigEndian>() as usize;
+    ///
+    ///     if buf.remaining() < len {
+    ///         return None;
+    ///     }
+    ///
+    ///     let mut frame = vec![0; len];
+    ///     buf.read_slice(&mut frame);
+    ///     buf.commit();
+    ///     Some(frame)
+    /// }
+    ///
+    /// # fn main() {
+    /// let mut buf = Cursor::new(b"\x00\x05hel");
+    ///
+    /// assert_eq!(None, decode(&mut buf));
+    /// assert_eq!(0, buf.position());
+    ///
+    /// let mut buf = Cursor::new(b"\x00\x05hello");
+    ///
+    /// assert_eq!(Some(b"hello".to_vec()), decode(&mut buf));
+    /// assert!(!buf.has_remaining());
+    /// # }
+    /// ```
+    fn guard<'a>(&'a mut self) -> Rewind<'a, Self> where Self: Sized {
+        let mark = self.checkpoint();
+
+        Rewind {
+            buf: self,
+            mark: Some(mark),
+        }
+    }
+}
+
+/// Rewinds a `Buf` to a checkpoint when dropped, unless `commit` is called.
+///
+/// Created by `Checkpoint::guard`. The `Buf` is accessed through `Deref`.
+pub struct Rewind<'a, B: Checkpoint + 'a> {
+    buf: &'a mut B,
+    // Only `None` once committed
+    mark: Option<B::Mark>,
+}
+
+impl<'a, B: Checkpoint + 'a> Rewind<'a, B> {
+    /// Keep the bytes read through the guard consumed
+    pub fn commit(mut self) {
+        self.mark = None;
+    }
+}
+
+impl<'a, B: Checkpoint + 'a> ops::Deref for Rewind<'a, B> {
+    type Target = B;
+
+    fn deref(&self) -> &B {
+        self.buf
+    }
+}
+
+impl<'a, B: Checkpoint + 'a> ops::DerefMut for Rewind<'a, B> {
+    fn deref_mut(&mut self) -> &mut B {
+        self.buf
+    }
+}
+
+impl<'a, B: Checkpoint + 'a> Drop for Rewind<'a, B> {
+    fn drop(&mut self) {
+        if let Some(mark) = self.mark.take() {
+            self.buf.rewind(mark);
+        }
+    }
+}
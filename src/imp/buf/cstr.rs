@@ -0,0 +1,106 @@
+//! NUL-terminated C strings.
+
+use {Buf, MutBuf, Bytes};
+use imp::buf::read_shared;
+use std::{cmp, error, fmt};
+
+/// An error returned when reading or writing a NUL-terminated string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CStrError {
+    /// The `Buf` ended before a NUL byte was found
+    NoTerminator,
+    /// No NUL byte was found within the maximum length allowed by the caller
+    TooLong,
+    /// The string to write contains a NUL byte
+    InteriorNul,
+}
+
+impl CStrError {
+    fn as_str(&self) -> &'static str {
+        match *self {
+            CStrError::NoTerminator => "buffer ended before the terminating NUL byte",
+            CStrError::TooLong => "string is too long",
+            CStrError::InteriorNul => "string contains an interior NUL byte",
+        }
+    }
+}
+
+impl fmt::Display for CStrError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str(self.as_str())
+    }
+}
+
+impl error::Error for CStrError {
+    fn description(&self) -> &str {
+        self.as_str()
+    }
+}
+
+/// Read bytes up to a NUL byte, consuming the NUL. See `Buf::read_cstr`.
+pub fn read_cstr<B: Buf + ?Sized>(buf: &mut B, max_len: usize) -> Result<Bytes, CStrError> {
+    // The NUL must be within the first `max_len + 1` bytes
+    let limit = cmp::min(max_len.saturating_add(1), buf.remaining());
+
+    match find_nul(buf, limit) {
+        Some(i) => {
+            let ret = read_shared(buf, i);
+            buf.advance(1);
+            Ok(ret)
+        }
+        None if limit > max_len => Err(CStrError::TooLong),
+        None => Err(CStrError::NoTerminator),
+    }
+}
+
+/// Returns the offset of the first NUL byte within the first `limit` bytes
+/// of `buf`, without consuming anything
+fn find_nul<B: Buf + ?Sized>(buf: &B, limit: usize) -> Option<usize> {
+    let mut searched = {
+        let src = buf.bytes();
+        let n = cmp::min(src.len(), limit);
+
+        if let Some(i) = src[..n].iter().position(|&b| b == 0) {
+            return Some(i);
+        }
+
+        n
+    };
+
+    // The string spans several chunks. Peek at a window that doubles in size
+    // each time, so the bytes are copied a bounded number of times.
+    let mut window = vec![];
+
+    while searched < limit {
+        let len = cmp::min(cmp::max(searched * 2, 64), limit);
+        window.resize(len, 0);
+
+        let n = buf.peek_slice(&mut window);
+
+        if let Some(i) = window[searched..n].iter().position(|&b| b == 0) {
+            return Some(searched + i);
+        }
+
+        if n < len {
+            // No more bytes can be peeked at
+            break;
+        }
+
+        searched = n;
+    }
+
+    None
+}
+
+/// Write `src` followed by a NUL byte. See `MutBuf::write_cstr`.
+pub fn write_cstr<B: MutBuf + ?Sized>(buf: &mut B, src: &[u8]) -> Result<(), CStrError> {
+    if src.contains(&0) {
+        return Err(CStrError::InteriorNul);
+    }
+
+    assert!(buf.remaining() > src.len(), "buffer overflow");
+
+    buf.write_slice(src);
+    buf.write_u8(0);
+    Ok(())
+}
@@ -0,0 +1,257 @@
+//! Byte orders selected at runtime.
+
+use {Buf, MutBuf, Bytes};
+use buf::{Checkpoint, SeekBuf, UninitSlice};
+use std::io;
+
+/// A byte order selected at runtime.
+///
+/// This is used for formats that announce their byte order in a header, such
+/// as TIFF or pcap. When the byte order is known at compile time, prefer the
+/// `ByteOrder` type parameter of methods such as `Buf::read_u32`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Endian {
+    /// Most significant byte first
+    Big,
+    /// Least significant byte first
+    Little,
+    /// The byte order of the target platform
+    Native,
+}
+
+impl Endian {
+    /// Returns true if the byte order is big-endian, resolving `Native` for
+    /// the target platform.
+    pub fn is_big(&self) -> bool {
+        match *self {
+            Endian::Big => true,
+            Endian::Little => false,
+            Endian::Native => cfg!(target_endian = "big"),
+        }
+    }
+
+    /// Returns true if the byte order is little-endian, resolving `Native`
+    /// for the target platform.
+    pub fn is_little(&self) -> bool {
+        !self.is_big()
+    }
+}
+
+/// A `Buf` or `MutBuf` adapter that reads and writes numbers in a byte order
+/// selected at runtime.
+///
+/// The numeric accessors of `EndianBuf` take no `ByteOrder` parameter, so
+/// code that is handed an `EndianBuf` does not need to know the byte order.
+///
+/// ```
+/// use bytes::buf::{Endian, EndianBuf};
+/// use std::io::Cursor;
+///
+/// // A TIFF header announces its byte order with "II" or "MM"
+/// let src = b"II\x2A\x00";
+///
+/// let endian = if &src[..2] == b"II" { Endian::Little } else { Endian::Big };
+/// let mut buf = EndianBuf::new(Cursor::new(&src[2..]), endian);
+///
+/// assert_eq!(42, buf.read_u16());
+/// ```
+#[derive(Debug)]
+pub struct EndianBuf<T> {
+    inner: T,
+    endian: Endian,
+}
+
+impl<T> EndianBuf<T> {
+    /// Create a new `EndianBuf` wrapping `inner`
+    pub fn new(inner: T, endian: Endian) -> EndianBuf<T> {
+        EndianBuf {
+            inner: inner,
+            endian: endian,
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Returns the byte order used by the numeric accessors
+    pub fn endian(&self) -> Endian {
+        self.endian
+    }
+
+    pub fn set_endian(&mut self, endian: Endian) {
+        self.endian = endian
+    }
+}
+
+macro_rules! read_methods {
+    ($($name:ident => $endian:ident -> $ty:ty, $doc:expr;)*) => {
+        impl<T: Buf> EndianBuf<T> {
+            $(
+                #[doc = $doc]
+                pub fn $name(&mut self) -> $ty {
+                    self.inner.$endian(self.endian)
+                }
+            )*
+
+            /// Reads an unsigned n-bytes integer
+            pub fn read_uint(&mut self, nbytes: usize) -> u64 {
+                self.inner.read_uint_endian(nbytes, self.endian)
+            }
+
+            /// Reads a signed n-bytes integer
+            pub fn read_int(&mut self, nbytes: usize) -> i64 {
+                self.inner.read_int_endian(nbytes, self.endian)
+            }
+        }
+    };
+}
+
+macro_rules! write_methods {
+    ($($name:ident => $endian:ident -> $ty:ty, $doc:expr;)*) => {
+        impl<T: MutBuf> EndianBuf<T> {
+            $(
+                #[doc = $doc]
+                pub fn $name(&mut self, n: $ty) {
+                    self.inner.$endian(n, self.endian)
+                }
+            )*
+
+            /// Writes an unsigned n-bytes integer
+            pub fn write_uint(&mut self, n: u64, nbytes: usize) {
+                self.inner.write_uint_endian(n, nbytes, self.endian)
+            }
+
+            /// Writes a signed n-bytes integer
+            pub fn write_int(&mut self, n: i64, nbytes: usize) {
+                self.inner.write_int_endian(n, nbytes, self.endian)
+            }
+        }
+    };
+}
+
+read_methods! {
+    read_u16 => read_u16_endian -> u16, "Reads an unsigned 16 bit integer";
+    read_i16 => read_i16_endian -> i16, "Reads a signed 16 bit integer";
+    read_u24 => read_u24_endian -> u32, "Reads an unsigned 24 bit integer";
+    read_i24 => read_i24_endian -> i32, "Reads a signed 24 bit integer, sign extending it to an `i32`";
+    read_u32 => read_u32_endian -> u32, "Reads an unsigned 32 bit integer";
+    read_i32 => read_i32_endian -> i32, "Reads a signed 32 bit integer";
+    read_u64 => read_u64_endian -> u64, "Reads an unsigned 64 bit integer";
+    read_i64 => read_i64_endian -> i64, "Reads a signed 64 bit integer";
+    read_u128 => read_u128_endian -> u128, "Reads an unsigned 128 bit integer";
+    read_i128 => read_i128_endian -> i128, "Reads a signed 128 bit integer";
+    read_f16 => read_f16_endian -> f32, "Reads a IEEE754 half-precision floating point number";
+    read_f32 => read_f32_endian -> f32, "Reads a IEEE754 single-precision floating point number";
+    read_f64 => read_f64_endian -> f64, "Reads a IEEE754 double-precision floating point number";
+}
+
+write_methods! {
+    write_u16 => write_u16_endian -> u16, "Writes an unsigned 16 bit integer";
+    write_i16 => write_i16_endian -> i16, "Writes a signed 16 bit integer";
+    write_u24 => write_u24_endian -> u32, "Writes an unsigned 24 bit integer";
+    write_i24 => write_i24_endian -> i32, "Writes a signed 24 bit integer";
+    write_u32 => write_u32_endian -> u32, "Writes an unsigned 32 bit integer";
+    write_i32 => write_i32_endian -> i32, "Writes a signed 32 bit integer";
+    write_u64 => write_u64_endian -> u64, "Writes an unsigned 64 bit integer";
+    write_i64 => write_i64_endian -> i64, "Writes a signed 64 bit integer";
+    write_u128 => write_u128_endian -> u128, "Writes an unsigned 128 bit integer";
+    write_i128 => write_i128_endian -> i128, "Writes a signed 128 bit integer";
+    write_f16 => write_f16_endian -> f32, "Writes a IEEE754 half-precision floating point number";
+    write_f32 => write_f32_endian -> f32, "Writes a IEEE754 single-precision floating point number";
+    write_f64 => write_f64_endian -> f64, "Writes a IEEE754 double-precision floating point number";
+}
+
+impl<T: Buf> Buf for EndianBuf<T> {
+    fn remaining(&self) -> usize {
+        self.inner.remaining()
+    }
+
+    fn bytes(&self) -> &[u8] {
+        self.inner.bytes()
+    }
+
+    fn advance(&mut self, cnt: usize) {
+        self.inner.advance(cnt)
+    }
+
+    fn bytes_shared(&self) -> Option<Bytes> {
+        self.inner.bytes_shared()
+    }
+
+    fn read_slice(&mut self, dst: &mut [u8]) {
+        self.inner.read_slice(dst)
+    }
+
+    fn peek_slice(&self, dst: &mut [u8]) -> usize {
+        self.inner.peek_slice(dst)
+    }
+}
+
+impl<T: SeekBuf> SeekBuf for EndianBuf<T> {
+    fn position(&self) -> usize {
+        self.inner.position()
+    }
+
+    fn set_position(&mut self, pos: usize) {
+        self.inner.set_position(pos)
+    }
+}
+
+impl<T: Checkpoint> Checkpoint for EndianBuf<T> {
+    type Mark = T::Mark;
+
+    fn checkpoint(&self) -> T::Mark {
+        self.inner.checkpoint()
+    }
+
+    fn rewind(&mut self, mark: T::Mark) {
+        self.inner.rewind(mark)
+    }
+}
+
+impl<T: MutBuf> MutBuf for EndianBuf<T> {
+    fn remaining(&self) -> usize {
+        self.inner.remaining()
+    }
+
+    fn is_bounded(&self) -> bool {
+        self.inner.is_bounded()
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        self.inner.reserve(additional)
+    }
+
+    unsafe fn advance(&mut self, cnt: usize) {
+        self.inner.advance(cnt)
+    }
+
+    fn flush_buf(&mut self) -> io::Result<()> {
+        self.inner.flush_buf()
+    }
+
+    fn mut_bytes(&mut self) -> &mut UninitSlice {
+        self.inner.mut_bytes()
+    }
+
+    fn mut_bytes_vec<'a>(&'a mut self, dst: &mut [&'a mut UninitSlice]) -> usize {
+        self.inner.mut_bytes_vec(dst)
+    }
+
+    fn init_bytes_vec<'a>(&'a mut self, dst: &mut [&'a mut [u8]], max: usize) -> usize {
+        self.inner.init_bytes_vec(dst, max)
+    }
+
+    fn release_unused(&mut self) {
+        self.inner.release_unused()
+    }
+}
@@ -1,15 +1,44 @@
 pub mod append;
+pub mod ascii;
+pub mod bits;
 pub mod block;
+pub mod checkpoint;
+mod bulk;
+pub mod cstr;
+pub mod endian;
+mod num;
+pub mod prefix;
 pub mod slice_buf;
 pub mod ring;
+pub mod stream;
 pub mod take;
-
-use {Bytes};
-use buf::Take;
-use byteorder::ByteOrder;
-use std::{cmp, fmt, io, ptr, usize};
+pub mod uninit;
+
+use {Bytes, BytesBuilder};
+use buf::{Checkpoint, CStrError, DecimalError, Endian, LengthPrefix, PrefixError, Take, UninitSlice};
+use byteorder::{ByteOrder, BigEndian, LittleEndian};
+use std::{cmp, fmt, io, isize, mem, ptr};
+use std::ffi::CStr;
+
+/// Call a `ByteOrder` function in the byte order selected at runtime
+macro_rules! with_endian {
+    ($endian:expr, $f:ident($($arg:expr),*)) => {
+        if $endian.is_big() {
+            BigEndian::$f($($arg),*)
+        } else {
+            LittleEndian::$f($($arg),*)
+        }
+    };
+}
 
 /// A trait for values that provide sequential read access to bytes.
+///
+/// `Buf` can be used as a trait object. Methods that are generic, such as
+/// `read_u16::<BigEndian>()`, are provided by `BufExt`, which is implemented
+/// for every `Buf` including `dyn Buf`. Methods that take `self` by value
+/// require `Self: Sized`, and are available on `Box<dyn Buf>` and
+/// `&mut dyn Buf` through the implementations of `Buf` for `Box<B>` and
+/// `&mut B`.
 pub trait Buf {
 
     /// Returns the number of bytes that can be accessed from the Buf
@@ -27,11 +56,13 @@ pub trait Buf {
         self.remaining() > 0
     }
 
-    fn copy_to<S: Sink + ?Sized>(&mut self, dst: &mut S) -> usize
-            where Self: Sized {
-        let rem = self.remaining();
-        dst.sink(self);
-        rem - self.remaining()
+    /// Returns the slice returned by `Buf::bytes()` as a `Bytes` value sharing
+    /// the underlying memory.
+    ///
+    /// Returns `None` if the `Buf` is not backed by reference counted memory,
+    /// in which case the bytes must be copied out.
+    fn bytes_shared(&self) -> Option<Bytes> {
+        None
     }
 
     /// Read bytes from the `Buf` into the given slice and advance the cursor by
@@ -81,6 +112,20 @@ pub trait Buf {
         }
     }
 
+    /// Copies bytes from the front of the `Buf` into `dst` without advancing
+    /// the buffer cursor, returning the number of bytes copied.
+    ///
+    /// The default implementation only copies from `bytes`. Buffers made of
+    /// several chunks override it to copy across chunks, in which case fewer
+    /// than `dst.len()` bytes are copied only if fewer remain.
+    fn peek_slice(&self, dst: &mut [u8]) -> usize {
+        let src = self.bytes();
+        let n = cmp::min(src.len(), dst.len());
+
+        dst[..n].copy_from_slice(&src[..n]);
+        n
+    }
+
     /// Reads an unsigned 8 bit integer from the `Buf`.
     fn read_u8(&mut self) -> u8 {
         let mut buf = [0; 1];
@@ -95,6 +140,288 @@ pub trait Buf {
         buf[0] as i8
     }
 
+    /// Reads an unsigned 16 bit integer from the `Buf` in the given byte order
+    fn read_u16_endian(&mut self, endian: Endian) -> u16 {
+        let mut buf = [0; 2];
+        self.read_slice(&mut buf);
+        with_endian!(endian, read_u16(&buf))
+    }
+
+    /// Reads a signed 16 bit integer from the `Buf` in the given byte order
+    fn read_i16_endian(&mut self, endian: Endian) -> i16 {
+        let mut buf = [0; 2];
+        self.read_slice(&mut buf);
+        with_endian!(endian, read_i16(&buf))
+    }
+
+    /// Reads an unsigned 24 bit integer from the `Buf` in the given byte order
+    fn read_u24_endian(&mut self, endian: Endian) -> u32 {
+        self.read_uint_endian(3, endian) as u32
+    }
+
+    /// Reads a signed 24 bit integer from the `Buf` in the given byte order,
+    /// sign extending it to an `i32`
+    fn read_i24_endian(&mut self, endian: Endian) -> i32 {
+        self.read_int_endian(3, endian) as i32
+    }
+
+    /// Reads an unsigned 32 bit integer from the `Buf` in the given byte order
+    fn read_u32_endian(&mut self, endian: Endian) -> u32 {
+        let mut buf = [0; 4];
+        self.read_slice(&mut buf);
+        with_endian!(endian, read_u32(&buf))
+    }
+
+    /// Reads a signed 32 bit integer from the `Buf` in the given byte order
+    fn read_i32_endian(&mut self, endian: Endian) -> i32 {
+        let mut buf = [0; 4];
+        self.read_slice(&mut buf);
+        with_endian!(endian, read_i32(&buf))
+    }
+
+    /// Reads an unsigned 64 bit integer from the `Buf` in the given byte order
+    fn read_u64_endian(&mut self, endian: Endian) -> u64 {
+        let mut buf = [0; 8];
+        self.read_slice(&mut buf);
+        with_endian!(endian, read_u64(&buf))
+    }
+
+    /// Reads a signed 64 bit integer from the `Buf` in the given byte order
+    fn read_i64_endian(&mut self, endian: Endian) -> i64 {
+        let mut buf = [0; 8];
+        self.read_slice(&mut buf);
+        with_endian!(endian, read_i64(&buf))
+    }
+
+    /// Reads an unsigned 128 bit integer from the `Buf` in the given byte
+    /// order
+    fn read_u128_endian(&mut self, endian: Endian) -> u128 {
+        let mut buf = [0; 16];
+        self.read_slice(&mut buf);
+
+        if endian.is_big() {
+            num::read_u128::<BigEndian>(&buf)
+        } else {
+            num::read_u128::<LittleEndian>(&buf)
+        }
+    }
+
+    /// Reads a signed 128 bit integer from the `Buf` in the given byte order
+    fn read_i128_endian(&mut self, endian: Endian) -> i128 {
+        self.read_u128_endian(endian) as i128
+    }
+
+    /// Reads an unsigned n-bytes integer from the `Buf` in the given byte
+    /// order
+    fn read_uint_endian(&mut self, nbytes: usize, endian: Endian) -> u64 {
+        let mut buf = [0; 8];
+        self.read_slice(&mut buf[..nbytes]);
+        with_endian!(endian, read_uint(&buf[..nbytes], nbytes))
+    }
+
+    /// Reads a signed n-bytes integer from the `Buf` in the given byte order
+    fn read_int_endian(&mut self, nbytes: usize, endian: Endian) -> i64 {
+        let mut buf = [0; 8];
+        self.read_slice(&mut buf[..nbytes]);
+        with_endian!(endian, read_int(&buf[..nbytes], nbytes))
+    }
+
+    /// Reads a IEEE754 half-precision (2 bytes) floating point number from
+    /// the `Buf` in the given byte order, converting it to an `f32`
+    fn read_f16_endian(&mut self, endian: Endian) -> f32 {
+        num::f16_to_f32(self.read_u16_endian(endian))
+    }
+
+    /// Reads a IEEE754 single-precision (4 bytes) floating point number from
+    /// the `Buf` in the given byte order
+    fn read_f32_endian(&mut self, endian: Endian) -> f32 {
+        let mut buf = [0; 4];
+        self.read_slice(&mut buf);
+        with_endian!(endian, read_f32(&buf))
+    }
+
+    /// Reads a IEEE754 double-precision (8 bytes) floating point number from
+    /// the `Buf` in the given byte order
+    fn read_f64_endian(&mut self, endian: Endian) -> f64 {
+        let mut buf = [0; 8];
+        self.read_slice(&mut buf);
+        with_endian!(endian, read_f64(&buf))
+    }
+
+    /// Reads an unsigned ASCII decimal integer from the `Buf`.
+    ///
+    /// Digits are consumed up to the first non-digit byte or the end of the
+    /// `Buf`, which may span several chunks. All of the digits are consumed
+    /// even if the number does not fit in a `u64`.
+    fn read_decimal_u64(&mut self) -> Result<u64, DecimalError> {
+        ascii::read_decimal(self)
+    }
+
+    /// Reads a signed ASCII decimal integer from the `Buf`.
+    ///
+    /// The digits may be preceded by a single `+` or `-`, which is consumed
+    /// even if no digits follow it. Otherwise behaves like
+    /// `read_decimal_u64`.
+    fn read_decimal_i64(&mut self) -> Result<i64, DecimalError> {
+        let neg = match self.bytes().first() {
+            Some(&b'-') => true,
+            Some(&b'+') => false,
+            _ => return ascii::read_decimal(self).and_then(|n| {
+                if n > i64::MAX as u64 {
+                    Err(DecimalError::Overflow)
+                } else {
+                    Ok(n as i64)
+                }
+            }),
+        };
+
+        self.advance(1);
+
+        let n = try!(ascii::read_decimal(self));
+
+        if neg {
+            if n > i64::MAX as u64 + 1 {
+                return Err(DecimalError::Overflow);
+            }
+
+            Ok((n as i64).wrapping_neg())
+        } else {
+            if n > i64::MAX as u64 {
+                return Err(DecimalError::Overflow);
+            }
+
+            Ok(n as i64)
+        }
+    }
+
+    /// Reads a length prefix of the given kind, followed by that many bytes.
+    ///
+    /// Returns `PrefixError::TooLong` if the length is greater than `max_len`,
+    /// which protects against hostile lengths when reading untrusted input.
+    ///
+    /// If the `Buf` is backed by reference counted memory, such as
+    /// `BytesBuf`, the returned `Bytes` shares that memory instead of copying.
+    ///
+    /// Nothing is consumed if an error is returned, so the read can be retried
+    /// once more bytes are available.
+    ///
+    /// ```
+    /// use bytes::{Buf, Bytes};
+    /// use bytes::buf::LengthPrefix;
+    ///
+    /// let src = Bytes::from(&b"\x05hello world"[..]);
+    /// let mut buf = src.buf();
+    ///
+    /// let hello = buf.read_prefixed_bytes(LengthPrefix::U8, 64).unwrap();
+    /// assert_eq!(hello.buf().bytes(), b"hello");
+    /// ```
+    fn read_prefixed_bytes(&mut self, kind: LengthPrefix, max_len: usize) -> Result<Bytes, PrefixError> {
+        prefix::read_prefixed(self, kind, max_len)
+    }
+
+    /// Reads a length prefix of the given kind, followed by that many bytes of
+    /// UTF-8.
+    ///
+    /// The prefix and payload are consumed if the payload is not valid UTF-8.
+    /// Otherwise, errors are reported as for `read_prefixed_bytes`.
+    fn read_prefixed_str(&mut self, kind: LengthPrefix, max_len: usize) -> Result<String, PrefixError> {
+        let bytes = try!(prefix::read_prefixed(self, kind, max_len));
+        let mut vec = Vec::with_capacity(bytes.len());
+        bytes.buf().copy_to(&mut vec);
+
+        String::from_utf8(vec).map_err(|_| PrefixError::InvalidUtf8)
+    }
+
+    /// Reads a NUL-terminated string, returning the bytes before the NUL.
+    ///
+    /// The string may span several chunks. If the `Buf` is backed by
+    /// reference counted memory, the returned `Bytes` shares that memory
+    /// instead of copying. The NUL is consumed but not returned.
+    ///
+    /// Returns `CStrError::TooLong` if no NUL is found within `max_len`
+    /// bytes, and `CStrError::NoTerminator` if the `Buf` ends first. Nothing
+    /// is consumed if an error is returned, so the read can be retried once
+    /// more bytes are available.
+    ///
+    /// ```
+    /// use bytes::Buf;
+    /// use std::io::Cursor;
+    ///
+    /// let mut buf = Cursor::new(b"eth0\0rest");
+    /// let name = buf.read_cstr(16).unwrap();
+    ///
+    /// assert_eq!(name.buf().bytes(), b"eth0");
+    /// assert_eq!(buf.bytes(), b"rest");
+    /// ```
+    fn read_cstr(&mut self, max_len: usize) -> Result<Bytes, CStrError> {
+        cstr::read_cstr(self, max_len)
+    }
+
+    /// Returns the NUL-terminated string at the start of the `Buf` without
+    /// consuming it, if its NUL is in the current chunk.
+    ///
+    /// Returns `None` if the current chunk does not contain a NUL, in which
+    /// case `read_cstr` can still read the string. To consume the string,
+    /// advance by the length of `to_bytes_with_nul()`.
+    fn peek_cstr(&self) -> Option<&CStr> {
+        let src = self.bytes();
+
+        src.iter().position(|&b| b == 0).map(|i| {
+            CStr::from_bytes_with_nul(&src[..i + 1]).unwrap()
+        })
+    }
+
+    /// Creates a "by reference" adaptor for this instance of Buf
+    fn by_ref(&mut self) -> &mut Self where Self: Sized {
+        self
+    }
+
+    /// Create an adapter which will limit at most `limit` bytes from it.
+    fn take(self, limit: usize) -> Take<Self> where Self: Sized {
+        Take::new(self, limit)
+    }
+
+    /// Return a `Reader` for the value. Allows using a `Buf` as an `io::Read`
+    fn reader(self) -> Reader<Self> where Self: Sized {
+        Reader::new(self)
+    }
+}
+
+/// Generic helpers for reading from a `Buf`.
+///
+/// These methods take a `ByteOrder` or `Sink` type parameter, which would
+/// keep them from being called on a `dyn Buf` if they were part of `Buf`.
+/// `BufExt` is implemented for every `Buf`, sized or not.
+///
+/// ```
+/// extern crate bytes;
+/// extern crate byteorder;
+///
+/// use bytes::{Buf, BufExt};
+/// use byteorder::BigEndian;
+/// use std::io::Cursor;
+///
+/// fn read_len(buf: &mut dyn Buf) -> u16 {
+///     buf.read_u16::<BigEndian>()
+/// }
+///
+/// # fn main() {
+/// let mut buf = Cursor::new(b"\x00\x05hello");
+/// assert_eq!(5, read_len(&mut buf));
+///
+/// let mut boxed: Box<dyn Buf> = Box::new(buf);
+/// assert_eq!(0x6865, boxed.read_u16::<BigEndian>());
+/// # }
+/// ```
+pub trait BufExt: Buf {
+    /// Copies bytes from the `Buf` into `dst` and returns the number of
+    /// bytes copied.
+    fn copy_to<S: Sink + ?Sized>(&mut self, dst: &mut S) -> usize {
+        let rem = self.remaining();
+        dst.sink(self);
+        rem - self.remaining()
+    }
+
     /// Reads an unsigned 16 bit integer from the `Buf`
     fn read_u16<T: ByteOrder>(&mut self) -> u16 {
         let mut buf = [0; 2];
@@ -109,6 +436,21 @@ pub trait Buf {
         T::read_i16(&buf)
     }
 
+    /// Reads an unsigned 24 bit integer from the `Buf`
+    fn read_u24<T: ByteOrder>(&mut self) -> u32 {
+        let mut buf = [0; 3];
+        self.read_slice(&mut buf);
+        T::read_uint(&buf, 3) as u32
+    }
+
+    /// Reads a signed 24 bit integer from the `Buf`, sign extending it to an
+    /// `i32`
+    fn read_i24<T: ByteOrder>(&mut self) -> i32 {
+        let mut buf = [0; 3];
+        self.read_slice(&mut buf);
+        T::read_int(&buf, 3) as i32
+    }
+
     /// Reads an unsigned 32 bit integer from the `Buf`
     fn read_u32<T: ByteOrder>(&mut self) -> u32 {
         let mut buf = [0; 4];
@@ -137,6 +479,18 @@ pub trait Buf {
         T::read_i64(&buf)
     }
 
+    /// Reads an unsigned 128 bit integer from the `Buf`
+    fn read_u128<T: ByteOrder>(&mut self) -> u128 {
+        let mut buf = [0; 16];
+        self.read_slice(&mut buf);
+        num::read_u128::<T>(&buf)
+    }
+
+    /// Reads a signed 128 bit integer from the `Buf`
+    fn read_i128<T: ByteOrder>(&mut self) -> i128 {
+        self.read_u128::<T>() as i128
+    }
+
     /// Reads an unsigned n-bytes integer from the `Buf`
     fn read_uint<T: ByteOrder>(&mut self, nbytes: usize) -> u64 {
         let mut buf = [0; 8];
@@ -151,6 +505,12 @@ pub trait Buf {
         T::read_int(&buf[..nbytes], nbytes)
     }
 
+    /// Reads a IEEE754 half-precision (2 bytes) floating point number from
+    /// the `Buf`, converting it to an `f32`. The conversion is exact.
+    fn read_f16<T: ByteOrder>(&mut self) -> f32 {
+        num::f16_to_f32(self.read_u16::<T>())
+    }
+
     /// Reads a IEEE754 single-precision (4 bytes) floating point number from
     /// the `Buf`
     fn read_f32<T: ByteOrder>(&mut self) -> f32 {
@@ -167,29 +527,85 @@ pub trait Buf {
         T::read_f64(&buf)
     }
 
-    /// Creates a "by reference" adaptor for this instance of Buf
-    fn by_ref(&mut self) -> &mut Self where Self: Sized {
-        self
+    /// Fills `dst` with unsigned 16 bit integers read from the `Buf`.
+    ///
+    /// This is equivalent to calling `read_u16` once per element, but copies
+    /// whole chunks at a time and byte swaps in bulk.
+    ///
+    /// ```
+    /// extern crate bytes;
+    /// extern crate byteorder;
+    ///
+    /// use bytes::BufExt;
+    /// use byteorder::BigEndian;
+    /// use std::io::Cursor;
+    ///
+    /// # fn main() {
+    /// let mut buf = Cursor::new(b"\x00\x01\x00\x02");
+    /// let mut dst = [0; 2];
+    ///
+    /// buf.read_u16_into::<BigEndian>(&mut dst);
+    /// assert_eq!(dst, [1, 2]);
+    /// # }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are not enough remaining bytes to fill `dst`.
+    fn read_u16_into<T: ByteOrder>(&mut self, dst: &mut [u16]) {
+        bulk::read_into::<T, _, _>(self, dst)
     }
 
-    /// Create an adapter which will limit at most `limit` bytes from it.
-    fn take(self, limit: usize) -> Take<Self> where Self: Sized {
-        Take::new(self, limit)
+    /// Fills `dst` with unsigned 32 bit integers read from the `Buf`.
+    ///
+    /// See `read_u16_into`.
+    fn read_u32_into<T: ByteOrder>(&mut self, dst: &mut [u32]) {
+        bulk::read_into::<T, _, _>(self, dst)
     }
 
-    /// Return a `Reader` for the value. Allows using a `Buf` as an `io::Read`
-    fn reader(self) -> Reader<Self> where Self: Sized {
-        Reader::new(self)
+    /// Fills `dst` with unsigned 64 bit integers read from the `Buf`.
+    ///
+    /// See `read_u16_into`.
+    fn read_u64_into<T: ByteOrder>(&mut self, dst: &mut [u64]) {
+        bulk::read_into::<T, _, _>(self, dst)
+    }
+
+    /// Fills `dst` with IEEE754 single-precision floating point numbers read
+    /// from the `Buf`.
+    ///
+    /// See `read_u16_into`.
+    fn read_f32_into<T: ByteOrder>(&mut self, dst: &mut [f32]) {
+        bulk::read_into::<T, _, _>(self, bulk::f32_as_u32_mut(dst))
+    }
+
+    /// Fills `dst` with IEEE754 double-precision floating point numbers read
+    /// from the `Buf`.
+    ///
+    /// See `read_u16_into`.
+    fn read_f64_into<T: ByteOrder>(&mut self, dst: &mut [f64]) {
+        bulk::read_into::<T, _, _>(self, bulk::f64_as_u64_mut(dst))
     }
 }
 
+impl<B: Buf + ?Sized> BufExt for B {
+}
+
 /// A trait for values that provide sequential write access to bytes.
+///
+/// Like `Buf`, `MutBuf` can be used as a trait object, with the generic
+/// methods provided by `MutBufExt`.
 pub trait MutBuf {
 
     /// Returns the number of bytes that can be written to the MutBuf
     fn remaining(&self) -> usize;
 
     /// Advance the internal cursor of the MutBuf
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that the first `cnt` bytes of the slice returned
+    /// by `mut_bytes` have been initialized. Prefer the safe `write_*` and
+    /// `put_*` functions, which handle this.
     unsafe fn advance(&mut self, cnt: usize);
 
     /// Returns true iff there is any more space for bytes to be written
@@ -197,17 +613,88 @@ pub trait MutBuf {
         self.remaining() > 0
     }
 
+    /// Returns true if the `MutBuf` has a fixed capacity.
+    ///
+    /// A buffer that is not bounded allocates more memory as it is written to.
+    /// In that case, `remaining` returns the maximum number of bytes the
+    /// buffer could grow to hold, not the amount of memory currently
+    /// allocated.
+    fn is_bounded(&self) -> bool {
+        true
+    }
+
+    /// Reserves capacity for at least `additional` more bytes, so that
+    /// `mut_bytes` returns a slice of at least that length.
+    ///
+    /// Bounded buffers cannot grow, so the default implementation does
+    /// nothing.
+    fn reserve(&mut self, additional: usize) {
+        let _ = additional;
+    }
+
     /// Returns a mutable slice starting at the current MutBuf position and of
     /// length between 0 and `MutBuf::remaining()`.
     ///
-    /// The returned byte slice may represent uninitialized memory.
-    unsafe fn mut_bytes(&mut self) -> &mut [u8];
+    /// The returned slice may represent uninitialized memory, which is why it
+    /// can only be written to.
+    fn mut_bytes(&mut self) -> &mut UninitSlice;
 
-    fn copy_from<S: Source>(&mut self, src: S) -> usize
-            where Self: Sized {
-        let rem = self.remaining();
-        src.source(self);
-        rem - self.remaining()
+    /// Fills `dst` with slices of the writable regions of the `MutBuf`,
+    /// starting at the current position, and returns the number of slices
+    /// that were filled.
+    ///
+    /// Buffers whose capacity is split across several regions, such as
+    /// `BlockBuf` and `RingBuf`, return more than one slice so that a single
+    /// vectored read can fill them all. Calling `advance(n)` afterwards
+    /// commits the first `n` bytes across the slices, in order.
+    ///
+    /// The default implementation fills `dst[0]` with `mut_bytes`.
+    fn mut_bytes_vec<'a>(&'a mut self, dst: &mut [&'a mut UninitSlice]) -> usize {
+        if dst.is_empty() || !self.has_remaining() {
+            return 0;
+        }
+
+        dst[0] = self.mut_bytes();
+        1
+    }
+
+    /// Fills `dst` with slices of initialized memory covering at most `max`
+    /// bytes of the writable regions of the `MutBuf`, and returns the number
+    /// of slices that were filled.
+    ///
+    /// This is used to pass the memory to `io::Read`. Buffers whose memory is
+    /// always initialized return it as is. The default implementation zeroes
+    /// the slices returned by `mut_bytes_vec`, up to `max` bytes and at most
+    /// 8 KiB per call, so that repeated reads into a buffer with a large
+    /// spare capacity, such as `Vec<u8>`, do not zero all of it each time.
+    fn init_bytes_vec<'a>(&'a mut self, dst: &mut [&'a mut [u8]], max: usize) -> usize {
+        let mut bytes: [&mut UninitSlice; MAX_IOVECS] = Default::default();
+        let len = cmp::min(dst.len(), MAX_IOVECS);
+        let n = self.mut_bytes_vec(&mut bytes[..len]);
+        let max = cmp::min(max, READ_CHUNK_LEN);
+        let mut rem = max;
+
+        fill_slices(dst, bytes[..n].iter_mut().map(|bytes| {
+            let len = cmp::min(bytes.len(), rem);
+            rem -= len;
+            mem::replace(bytes, Default::default())[..len].zeroed()
+        }), max)
+    }
+
+    /// Releases memory that `mut_bytes_vec` or `init_bytes_vec` allocated
+    /// ahead of writes but that was not written to.
+    ///
+    /// `ReadExt` calls this method when a read fails. Most buffers do not
+    /// allocate ahead of writes, so the default implementation does nothing.
+    fn release_unused(&mut self) {
+    }
+
+    /// Flushes any bytes held by the `MutBuf` to the destination it wraps.
+    ///
+    /// Most buffers only hold bytes in memory, so the default implementation
+    /// does nothing. `Writer::flush` calls this method.
+    fn flush_buf(&mut self) -> io::Result<()> {
+        Ok(())
     }
 
     /// Write bytes from the given slice into the `MutBuf` and advance the
@@ -230,27 +717,73 @@ pub trait MutBuf {
     /// assert_eq!(b"hello\0", &dst);
     /// ```
     fn write_slice(&mut self, src: &[u8]) {
+        assert!(self.remaining() >= src.len(), "buffer overflow");
+        self.put_slice(src);
+    }
+
+    /// Write as many bytes from the given slice as fit into the `MutBuf` and
+    /// advance the cursor by the number of bytes written.
+    ///
+    /// Returns the number of bytes written. Unlike `write_slice`, this does
+    /// not panic when the buffer is too small.
+    ///
+    /// ```
+    /// use bytes::MutBuf;
+    /// use std::io::Cursor;
+    ///
+    /// let mut dst = [0; 6];
+    /// let mut buf = Cursor::new(&mut dst[..]);
+    ///
+    /// assert_eq!(5, buf.put_slice(b"hello"));
+    /// assert_eq!(1, buf.put_slice(b" world"));
+    /// assert_eq!(0, buf.remaining());
+    /// ```
+    fn put_slice(&mut self, src: &[u8]) -> usize {
         let mut off = 0;
 
-        assert!(self.remaining() >= src.len(), "buffer overflow");
+        self.reserve(src.len());
 
         while off < src.len() {
-            let cnt;
-
-            unsafe {
+            let cnt = {
                 let dst = self.mut_bytes();
-                cnt = cmp::min(dst.len(), src.len() - off);
-
-                ptr::copy_nonoverlapping(
-                    src[off..].as_ptr(),
-                    dst.as_mut_ptr(),
-                    cnt);
+                let cnt = cmp::min(dst.len(), src.len() - off);
 
-                off += cnt;
+                dst[..cnt].copy_from_slice(&src[off..off + cnt]);
+                cnt
+            };
 
+            if cnt == 0 {
+                break;
             }
 
             unsafe { self.advance(cnt); }
+            off += cnt;
+        }
+
+        off
+    }
+
+    /// Writes `cnt` copies of `val` to the `MutBuf`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is not enough remaining capacity.
+    fn put_bytes(&mut self, val: u8, mut cnt: usize) {
+        assert!(self.remaining() >= cnt, "buffer overflow");
+
+        while cnt > 0 {
+            let n = {
+                let dst = self.mut_bytes();
+                let n = cmp::min(dst.len(), cnt);
+
+                unsafe { ptr::write_bytes(dst.as_mut_ptr(), val, n); }
+                n
+            };
+
+            assert!(n > 0, "buffer overflow");
+
+            unsafe { self.advance(n); }
+            cnt -= n;
         }
     }
 
@@ -268,98 +801,600 @@ pub trait MutBuf {
         self.write_slice(&[n as u8])
     }
 
-    /// Writes an unsigned 16 bit integer to the MutBuf.
-    fn write_u16<T: ByteOrder>(&mut self, n: u16) {
+    /// Writes an unsigned 16 bit integer to the MutBuf in the given byte order.
+    fn write_u16_endian(&mut self, n: u16, endian: Endian) {
         let mut buf = [0; 2];
-        T::write_u16(&mut buf, n);
+        with_endian!(endian, write_u16(&mut buf, n));
         self.write_slice(&buf)
     }
 
-    /// Writes a signed 16 bit integer to the MutBuf.
-    fn write_i16<T: ByteOrder>(&mut self, n: i16) {
+    /// Writes a signed 16 bit integer to the MutBuf in the given byte order.
+    fn write_i16_endian(&mut self, n: i16, endian: Endian) {
         let mut buf = [0; 2];
-        T::write_i16(&mut buf, n);
+        with_endian!(endian, write_i16(&mut buf, n));
+        self.write_slice(&buf)
+    }
+
+    /// Writes an unsigned 24 bit integer to the MutBuf in the given byte order.
+    ///
+    /// If `n` does not fit in 24 bits, this method panics.
+    fn write_u24_endian(&mut self, n: u32, endian: Endian) {
+        assert!(n < 1 << 24, "{} does not fit in 24 bits", n);
+        self.write_uint_endian(n as u64, 3, endian)
+    }
+
+    /// Writes a signed 24 bit integer to the MutBuf in the given byte order.
+    ///
+    /// If `n` does not fit in 24 bits, this method panics.
+    fn write_i24_endian(&mut self, n: i32, endian: Endian) {
+        assert!(n >= -(1 << 23) && n < 1 << 23, "{} does not fit in 24 bits", n);
+        self.write_int_endian(n as i64, 3, endian)
+    }
+
+    /// Writes an unsigned 32 bit integer to the MutBuf in the given byte order.
+    fn write_u32_endian(&mut self, n: u32, endian: Endian) {
+        let mut buf = [0; 4];
+        with_endian!(endian, write_u32(&mut buf, n));
+        self.write_slice(&buf)
+    }
+
+    /// Writes a signed 32 bit integer to the MutBuf in the given byte order.
+    fn write_i32_endian(&mut self, n: i32, endian: Endian) {
+        let mut buf = [0; 4];
+        with_endian!(endian, write_i32(&mut buf, n));
+        self.write_slice(&buf)
+    }
+
+    /// Writes an unsigned 64 bit integer to the MutBuf in the given byte order.
+    fn write_u64_endian(&mut self, n: u64, endian: Endian) {
+        let mut buf = [0; 8];
+        with_endian!(endian, write_u64(&mut buf, n));
         self.write_slice(&buf)
     }
 
-    /// Writes an unsigned 32 bit integer to the MutBuf.
-    fn write_u32<T: ByteOrder>(&mut self, n: u32) {
-        let mut buf = [0; 4];
-        T::write_u32(&mut buf, n);
-        self.write_slice(&buf)
+    /// Writes a signed 64 bit integer to the MutBuf in the given byte order.
+    fn write_i64_endian(&mut self, n: i64, endian: Endian) {
+        let mut buf = [0; 8];
+        with_endian!(endian, write_i64(&mut buf, n));
+        self.write_slice(&buf)
+    }
+
+    /// Writes an unsigned 128 bit integer to the MutBuf in the given byte
+    /// order.
+    fn write_u128_endian(&mut self, n: u128, endian: Endian) {
+        let mut buf = [0; 16];
+
+        if endian.is_big() {
+            num::write_u128::<BigEndian>(&mut buf, n);
+        } else {
+            num::write_u128::<LittleEndian>(&mut buf, n);
+        }
+
+        self.write_slice(&buf)
+    }
+
+    /// Writes a signed 128 bit integer to the MutBuf in the given byte order.
+    fn write_i128_endian(&mut self, n: i128, endian: Endian) {
+        self.write_u128_endian(n as u128, endian)
+    }
+
+    /// Writes an unsigned n-bytes integer to the MutBuf in the given byte
+    /// order.
+    ///
+    /// If the given integer is not representable in the given number of bytes,
+    /// this method panics. If `nbytes > 8`, this method panics.
+    fn write_uint_endian(&mut self, n: u64, nbytes: usize, endian: Endian) {
+        let mut buf = [0; 8];
+        with_endian!(endian, write_uint(&mut buf, n, nbytes));
+        self.write_slice(&buf[..nbytes])
+    }
+
+    /// Writes a signed n-bytes integer to the MutBuf in the given byte order.
+    ///
+    /// If the given integer is not representable in the given number of bytes,
+    /// this method panics. If `nbytes > 8`, this method panics.
+    fn write_int_endian(&mut self, n: i64, nbytes: usize, endian: Endian) {
+        let mut buf = [0; 8];
+        with_endian!(endian, write_int(&mut buf, n, nbytes));
+        self.write_slice(&buf[..nbytes])
+    }
+
+    /// Writes a IEEE754 half-precision (2 bytes) floating point number to the
+    /// MutBuf in the given byte order, rounding `n` to the nearest
+    /// representable value.
+    fn write_f16_endian(&mut self, n: f32, endian: Endian) {
+        self.write_u16_endian(num::f32_to_f16(n), endian)
+    }
+
+    /// Writes a IEEE754 single-precision (4 bytes) floating point number to
+    /// the MutBuf in the given byte order.
+    fn write_f32_endian(&mut self, n: f32, endian: Endian) {
+        let mut buf = [0; 4];
+        with_endian!(endian, write_f32(&mut buf, n));
+        self.write_slice(&buf)
+    }
+
+    /// Writes a IEEE754 double-precision (8 bytes) floating point number to
+    /// the MutBuf in the given byte order.
+    fn write_f64_endian(&mut self, n: f64, endian: Endian) {
+        let mut buf = [0; 8];
+        with_endian!(endian, write_f64(&mut buf, n));
+        self.write_slice(&buf)
+    }
+
+    /// Writes an unsigned integer to the MutBuf as ASCII decimal digits.
+    ///
+    /// If there is not enough remaining capacity, this method panics.
+    fn write_decimal_u64(&mut self, n: u64) {
+        let mut buf = [0; ascii::MAX_DECIMAL_LEN];
+        let pos = ascii::format_decimal(n, &mut buf);
+        self.write_slice(&buf[pos..])
+    }
+
+    /// Writes a signed integer to the MutBuf as ASCII decimal digits, preceded
+    /// by `-` if it is negative.
+    ///
+    /// If there is not enough remaining capacity, this method panics.
+    fn write_decimal_i64(&mut self, n: i64) {
+        let mut buf = [0; ascii::MAX_DECIMAL_LEN + 1];
+        let mut pos = ascii::format_decimal(n.unsigned_abs(), &mut buf);
+
+        if n < 0 {
+            pos -= 1;
+            buf[pos] = b'-';
+        }
+
+        self.write_slice(&buf[pos..])
+    }
+
+    /// Writes an unsigned integer to the MutBuf as lowercase ASCII hexadecimal
+    /// digits, without a `0x` prefix.
+    ///
+    /// If there is not enough remaining capacity, this method panics.
+    fn write_hex_u64(&mut self, n: u64) {
+        let mut buf = [0; ascii::MAX_HEX_LEN];
+        let pos = ascii::format_hex(n, &mut buf);
+        self.write_slice(&buf[pos..])
+    }
+
+    /// Writes a double-precision floating point number to the MutBuf as ASCII,
+    /// using the shortest representation that parses back to the same value.
+    ///
+    /// The output matches the `Debug` formatting of `f64`, such as `1.0`,
+    /// `1e-7`, `inf` or `NaN`.
+    ///
+    /// If there is not enough remaining capacity, this method panics.
+    fn write_decimal_f64(&mut self, n: f64) {
+        use std::fmt::Write;
+
+        let mut buf = ascii::StackBuf::new();
+        write!(buf, "{:?}", n).unwrap();
+        self.write_slice(buf.as_slice())
+    }
+
+    /// Writes a single-precision floating point number to the MutBuf as ASCII,
+    /// using the shortest representation that parses back to the same value.
+    ///
+    /// If there is not enough remaining capacity, this method panics.
+    fn write_decimal_f32(&mut self, n: f32) {
+        use std::fmt::Write;
+
+        let mut buf = ascii::StackBuf::new();
+        write!(buf, "{:?}", n).unwrap();
+        self.write_slice(buf.as_slice())
+    }
+
+    /// Writes a length prefix of the given kind, followed by `src`.
+    ///
+    /// Varint prefixes are written using as few bytes as possible. Nothing is
+    /// written if this method panics.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `src` does not fit in the prefix or if there is
+    /// not enough remaining capacity for both the prefix and `src`.
+    fn write_prefixed(&mut self, kind: LengthPrefix, src: &[u8]) {
+        prefix::write_prefixed(self, kind, src)
+    }
+
+    /// Writes `src` followed by a NUL byte.
+    ///
+    /// Returns `CStrError::InteriorNul` without writing anything if `src`
+    /// contains a NUL byte.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is not enough remaining capacity for `src` and the
+    /// NUL.
+    fn write_cstr(&mut self, src: &[u8]) -> Result<(), CStrError> {
+        cstr::write_cstr(self, src)
+    }
+
+    /// Creates a "by reference" adaptor for this instance of MutBuf
+    fn by_ref(&mut self) -> &mut Self where Self: Sized {
+        self
+    }
+
+    /// Create an adapter which will limit at most `limit` bytes from it.
+    fn take(self, limit: usize) -> Take<Self> where Self: Sized {
+        Take::new(self, limit)
+    }
+
+    /// Return a `Write` for the value. Allows using a `MutBuf` as an
+    /// `io::Write`
+    fn writer(self) -> Writer<Self> where Self: Sized {
+        Writer::new(self)
+    }
+}
+
+/// Generic helpers for writing to a `MutBuf`.
+///
+/// Like `BufExt`, this trait is implemented for every `MutBuf`, sized or
+/// not, so that its methods can be called on a `dyn MutBuf`.
+pub trait MutBufExt: MutBuf {
+    /// Copies bytes from `src` into the `MutBuf` and returns the number of
+    /// bytes copied.
+    fn copy_from<S: Source>(&mut self, src: S) -> usize {
+        let rem = self.remaining();
+        src.source(self);
+        rem - self.remaining()
+    }
+
+    /// Writes an unsigned 16 bit integer to the MutBuf.
+    fn write_u16<T: ByteOrder>(&mut self, n: u16) {
+        let mut buf = [0; 2];
+        T::write_u16(&mut buf, n);
+        self.write_slice(&buf)
+    }
+
+    /// Writes a signed 16 bit integer to the MutBuf.
+    fn write_i16<T: ByteOrder>(&mut self, n: i16) {
+        let mut buf = [0; 2];
+        T::write_i16(&mut buf, n);
+        self.write_slice(&buf)
+    }
+
+    /// Writes an unsigned 24 bit integer to the MutBuf.
+    ///
+    /// If `n` does not fit in 24 bits, this method panics.
+    fn write_u24<T: ByteOrder>(&mut self, n: u32) {
+        assert!(n < 1 << 24, "{} does not fit in 24 bits", n);
+
+        let mut buf = [0; 3];
+        T::write_uint(&mut buf, n as u64, 3);
+        self.write_slice(&buf)
+    }
+
+    /// Writes a signed 24 bit integer to the MutBuf.
+    ///
+    /// If `n` does not fit in 24 bits, this method panics.
+    fn write_i24<T: ByteOrder>(&mut self, n: i32) {
+        assert!(n >= -(1 << 23) && n < 1 << 23, "{} does not fit in 24 bits", n);
+
+        let mut buf = [0; 3];
+        T::write_int(&mut buf, n as i64, 3);
+        self.write_slice(&buf)
+    }
+
+    /// Writes an unsigned 32 bit integer to the MutBuf.
+    fn write_u32<T: ByteOrder>(&mut self, n: u32) {
+        let mut buf = [0; 4];
+        T::write_u32(&mut buf, n);
+        self.write_slice(&buf)
+    }
+
+    /// Writes a signed 32 bit integer to the MutBuf.
+    fn write_i32<T: ByteOrder>(&mut self, n: i32) {
+        let mut buf = [0; 4];
+        T::write_i32(&mut buf, n);
+        self.write_slice(&buf)
+    }
+
+    /// Writes an unsigned 64 bit integer to the MutBuf.
+    fn write_u64<T: ByteOrder>(&mut self, n: u64) {
+        let mut buf = [0; 8];
+        T::write_u64(&mut buf, n);
+        self.write_slice(&buf)
+    }
+
+    /// Writes a signed 64 bit integer to the MutBuf.
+    fn write_i64<T: ByteOrder>(&mut self, n: i64) {
+        let mut buf = [0; 8];
+        T::write_i64(&mut buf, n);
+        self.write_slice(&buf)
+    }
+
+    /// Writes an unsigned 128 bit integer to the MutBuf.
+    fn write_u128<T: ByteOrder>(&mut self, n: u128) {
+        let mut buf = [0; 16];
+        num::write_u128::<T>(&mut buf, n);
+        self.write_slice(&buf)
+    }
+
+    /// Writes a signed 128 bit integer to the MutBuf.
+    fn write_i128<T: ByteOrder>(&mut self, n: i128) {
+        self.write_u128::<T>(n as u128)
+    }
+
+    /// Writes an unsigned n-bytes integer to the MutBuf.
+    ///
+    /// If the given integer is not representable in the given number of bytes,
+    /// this method panics. If `nbytes > 8`, this method panics.
+    fn write_uint<T: ByteOrder>(&mut self, n: u64, nbytes: usize) {
+        let mut buf = [0; 8];
+        T::write_uint(&mut buf, n, nbytes);
+        self.write_slice(&buf[0..nbytes])
+    }
+
+    /// Writes a signed n-bytes integer to the MutBuf.
+    ///
+    /// If the given integer is not representable in the given number of bytes,
+    /// this method panics. If `nbytes > 8`, this method panics.
+    fn write_int<T: ByteOrder>(&mut self, n: i64, nbytes: usize) {
+        let mut buf = [0; 8];
+        T::write_int(&mut buf, n, nbytes);
+        self.write_slice(&buf[0..nbytes])
+    }
+
+    /// Writes an `f32` to the MutBuf as a IEEE754 half-precision (2 bytes)
+    /// floating point number.
+    ///
+    /// The value is rounded to the nearest representable half-precision
+    /// number, ties to even. Values too large to be represented are written
+    /// as infinity.
+    fn write_f16<T: ByteOrder>(&mut self, n: f32) {
+        self.write_u16::<T>(num::f32_to_f16(n))
+    }
+
+    /// Writes a IEEE754 single-precision (4 bytes) floating point number to
+    /// the MutBuf.
+    fn write_f32<T: ByteOrder>(&mut self, n: f32) {
+        let mut buf = [0; 4];
+        T::write_f32(&mut buf, n);
+        self.write_slice(&buf)
+    }
+
+    /// Writes a IEEE754 double-precision (8 bytes) floating point number to
+    /// the MutBuf.
+    fn write_f64<T: ByteOrder>(&mut self, n: f64) {
+        let mut buf = [0; 8];
+        T::write_f64(&mut buf, n);
+        self.write_slice(&buf)
+    }
+
+    /// Writes all of the unsigned 16 bit integers in `src` to the MutBuf.
+    ///
+    /// This is equivalent to calling `write_u16` once per element, but byte
+    /// swaps in bulk and copies whole chunks at a time.
+    ///
+    /// ```
+    /// extern crate bytes;
+    /// extern crate byteorder;
+    ///
+    /// use bytes::MutBufExt;
+    /// use byteorder::BigEndian;
+    ///
+    /// # fn main() {
+    /// let mut buf = vec![];
+    /// buf.write_u16_slice::<BigEndian>(&[1, 2]);
+    ///
+    /// assert_eq!(buf, b"\x00\x01\x00\x02");
+    /// # }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is not enough remaining capacity for all of `src`.
+    fn write_u16_slice<T: ByteOrder>(&mut self, src: &[u16]) {
+        bulk::write_from::<T, _, _>(self, src)
+    }
+
+    /// Writes all of the unsigned 32 bit integers in `src` to the MutBuf.
+    ///
+    /// See `write_u16_slice`.
+    fn write_u32_slice<T: ByteOrder>(&mut self, src: &[u32]) {
+        bulk::write_from::<T, _, _>(self, src)
+    }
+
+    /// Writes all of the unsigned 64 bit integers in `src` to the MutBuf.
+    ///
+    /// See `write_u16_slice`.
+    fn write_u64_slice<T: ByteOrder>(&mut self, src: &[u64]) {
+        bulk::write_from::<T, _, _>(self, src)
+    }
+
+    /// Writes all of the IEEE754 single-precision floating point numbers in
+    /// `src` to the MutBuf.
+    ///
+    /// See `write_u16_slice`.
+    fn write_f32_slice<T: ByteOrder>(&mut self, src: &[f32]) {
+        bulk::write_from::<T, _, _>(self, bulk::f32_as_u32(src))
+    }
+
+    /// Writes all of the IEEE754 double-precision floating point numbers in
+    /// `src` to the MutBuf.
+    ///
+    /// See `write_u16_slice`.
+    fn write_f64_slice<T: ByteOrder>(&mut self, src: &[f64]) {
+        bulk::write_from::<T, _, _>(self, bulk::f64_as_u64(src))
+    }
+}
+
+impl<B: MutBuf + ?Sized> MutBufExt for B {
+}
+
+/// A `Buf` that can be repositioned to any byte between its start and end.
+///
+/// The position counts the bytes that have been read, so the total length of
+/// the buffer is `position() + remaining()`.
+pub trait SeekBuf: Buf {
+    /// Returns the number of bytes between the start of the buffer and the
+    /// read cursor
+    fn position(&self) -> usize;
+
+    /// Moves the read cursor to `pos` bytes from the start of the buffer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pos` is past the end of the buffer.
+    fn set_position(&mut self, pos: usize);
+}
+
+/*
+ *
+ * ===== Trait objects =====
+ *
+ */
+
+impl<'a, B: Buf + ?Sized> Buf for &'a mut B {
+    fn remaining(&self) -> usize {
+        (**self).remaining()
+    }
+
+    fn bytes(&self) -> &[u8] {
+        (**self).bytes()
+    }
+
+    fn advance(&mut self, cnt: usize) {
+        (**self).advance(cnt)
+    }
+
+    fn has_remaining(&self) -> bool {
+        (**self).has_remaining()
+    }
+
+    fn bytes_shared(&self) -> Option<Bytes> {
+        (**self).bytes_shared()
+    }
+
+    fn read_slice(&mut self, dst: &mut [u8]) {
+        (**self).read_slice(dst)
+    }
+
+    fn peek_slice(&self, dst: &mut [u8]) -> usize {
+        (**self).peek_slice(dst)
+    }
+}
+
+impl<B: Buf + ?Sized> Buf for Box<B> {
+    fn remaining(&self) -> usize {
+        (**self).remaining()
+    }
+
+    fn bytes(&self) -> &[u8] {
+        (**self).bytes()
+    }
+
+    fn advance(&mut self, cnt: usize) {
+        (**self).advance(cnt)
+    }
+
+    fn has_remaining(&self) -> bool {
+        (**self).has_remaining()
+    }
+
+    fn bytes_shared(&self) -> Option<Bytes> {
+        (**self).bytes_shared()
+    }
+
+    fn read_slice(&mut self, dst: &mut [u8]) {
+        (**self).read_slice(dst)
+    }
+
+    fn peek_slice(&self, dst: &mut [u8]) -> usize {
+        (**self).peek_slice(dst)
+    }
+}
+
+impl<'a, B: MutBuf + ?Sized> MutBuf for &'a mut B {
+    fn remaining(&self) -> usize {
+        (**self).remaining()
+    }
+
+    unsafe fn advance(&mut self, cnt: usize) {
+        (**self).advance(cnt)
+    }
+
+    fn has_remaining(&self) -> bool {
+        (**self).has_remaining()
+    }
+
+    fn is_bounded(&self) -> bool {
+        (**self).is_bounded()
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        (**self).reserve(additional)
+    }
+
+    fn mut_bytes(&mut self) -> &mut UninitSlice {
+        (**self).mut_bytes()
+    }
+
+    fn mut_bytes_vec<'b>(&'b mut self, dst: &mut [&'b mut UninitSlice]) -> usize {
+        (**self).mut_bytes_vec(dst)
+    }
+
+    fn init_bytes_vec<'b>(&'b mut self, dst: &mut [&'b mut [u8]], max: usize) -> usize {
+        (**self).init_bytes_vec(dst, max)
+    }
+
+    fn release_unused(&mut self) {
+        (**self).release_unused()
+    }
+
+    fn flush_buf(&mut self) -> io::Result<()> {
+        (**self).flush_buf()
+    }
+
+    fn write_slice(&mut self, src: &[u8]) {
+        (**self).write_slice(src)
     }
+}
 
-    /// Writes a signed 32 bit integer to the MutBuf.
-    fn write_i32<T: ByteOrder>(&mut self, n: i32) {
-        let mut buf = [0; 4];
-        T::write_i32(&mut buf, n);
-        self.write_slice(&buf)
+impl<B: MutBuf + ?Sized> MutBuf for Box<B> {
+    fn remaining(&self) -> usize {
+        (**self).remaining()
     }
 
-    /// Writes an unsigned 64 bit integer to the MutBuf.
-    fn write_u64<T: ByteOrder>(&mut self, n: u64) {
-        let mut buf = [0; 8];
-        T::write_u64(&mut buf, n);
-        self.write_slice(&buf)
+    unsafe fn advance(&mut self, cnt: usize) {
+        (**self).advance(cnt)
     }
 
-    /// Writes a signed 64 bit integer to the MutBuf.
-    fn write_i64<T: ByteOrder>(&mut self, n: i64) {
-        let mut buf = [0; 8];
-        T::write_i64(&mut buf, n);
-        self.write_slice(&buf)
+    fn has_remaining(&self) -> bool {
+        (**self).has_remaining()
     }
 
-    /// Writes an unsigned n-bytes integer to the MutBuf.
-    ///
-    /// If the given integer is not representable in the given number of bytes,
-    /// this method panics. If `nbytes > 8`, this method panics.
-    fn write_uint<T: ByteOrder>(&mut self, n: u64, nbytes: usize) {
-        let mut buf = [0; 8];
-        T::write_uint(&mut buf, n, nbytes);
-        self.write_slice(&buf[0..nbytes])
+    fn is_bounded(&self) -> bool {
+        (**self).is_bounded()
     }
 
-    /// Writes a signed n-bytes integer to the MutBuf.
-    ///
-    /// If the given integer is not representable in the given number of bytes,
-    /// this method panics. If `nbytes > 8`, this method panics.
-    fn write_int<T: ByteOrder>(&mut self, n: i64, nbytes: usize) {
-        let mut buf = [0; 8];
-        T::write_int(&mut buf, n, nbytes);
-        self.write_slice(&buf[0..nbytes])
+    fn reserve(&mut self, additional: usize) {
+        (**self).reserve(additional)
     }
 
-    /// Writes a IEEE754 single-precision (4 bytes) floating point number to
-    /// the MutBuf.
-    fn write_f32<T: ByteOrder>(&mut self, n: f32) {
-        let mut buf = [0; 4];
-        T::write_f32(&mut buf, n);
-        self.write_slice(&buf)
+    fn mut_bytes(&mut self) -> &mut UninitSlice {
+        (**self).mut_bytes()
     }
 
-    /// Writes a IEEE754 double-precision (8 bytes) floating point number to
-    /// the MutBuf.
-    fn write_f64<T: ByteOrder>(&mut self, n: f64) {
-        let mut buf = [0; 8];
-        T::write_f64(&mut buf, n);
-        self.write_slice(&buf)
+    fn mut_bytes_vec<'b>(&'b mut self, dst: &mut [&'b mut UninitSlice]) -> usize {
+        (**self).mut_bytes_vec(dst)
     }
 
-    /// Creates a "by reference" adaptor for this instance of MutBuf
-    fn by_ref(&mut self) -> &mut Self where Self: Sized {
-        self
+    fn init_bytes_vec<'b>(&'b mut self, dst: &mut [&'b mut [u8]], max: usize) -> usize {
+        (**self).init_bytes_vec(dst, max)
     }
 
-    /// Create an adapter which will limit at most `limit` bytes from it.
-    fn take(self, limit: usize) -> Take<Self> where Self: Sized {
-        Take::new(self, limit)
+    fn release_unused(&mut self) {
+        (**self).release_unused()
     }
 
-    /// Return a `Write` for the value. Allows using a `MutBuf` as an
-    /// `io::Write`
-    fn writer(self) -> Writer<Self> where Self: Sized {
-        Writer::new(self)
+    fn flush_buf(&mut self) -> io::Result<()> {
+        (**self).flush_buf()
+    }
+
+    fn write_slice(&mut self, src: &[u8]) {
+        (**self).write_slice(src)
     }
 }
 
@@ -441,48 +1476,45 @@ impl<'a> IntoBuf for &'a () {
 /// A value that writes bytes from itself into a `MutBuf`.
 pub trait Source {
     /// Copy data from self into destination buffer
-    fn source<B: MutBuf>(self, buf: &mut B);
+    fn source<B: MutBuf + ?Sized>(self, buf: &mut B);
 }
 
 impl<'a> Source for &'a [u8] {
-    fn source<B: MutBuf>(self, buf: &mut B) {
+    fn source<B: MutBuf + ?Sized>(self, buf: &mut B) {
         buf.write_slice(self);
     }
 }
 
 impl Source for u8 {
-    fn source<B: MutBuf>(self, buf: &mut B) {
+    fn source<B: MutBuf + ?Sized>(self, buf: &mut B) {
         let src = [self];
         buf.write_slice(&src);
     }
 }
 
 impl Source for Bytes {
-    fn source<B: MutBuf>(self, buf: &mut B) {
+    fn source<B: MutBuf + ?Sized>(self, buf: &mut B) {
         Source::source(&self, buf);
     }
 }
 
 impl<'a> Source for &'a Bytes {
-    fn source<B: MutBuf>(self, buf: &mut B) {
+    fn source<B: MutBuf + ?Sized>(self, buf: &mut B) {
         Source::source(&mut self.buf(), buf);
     }
 }
 
-impl<'a, T: Buf> Source for &'a mut T {
-    fn source<B: MutBuf>(mut self, buf: &mut B) {
+impl<'a, T: Buf + ?Sized> Source for &'a mut T {
+    fn source<B: MutBuf + ?Sized>(mut self, buf: &mut B) {
         while self.has_remaining() && buf.has_remaining() {
             let l;
 
-            unsafe {
+            {
                 let s = self.bytes();
                 let d = buf.mut_bytes();
                 l = cmp::min(s.len(), d.len());
 
-                ptr::copy_nonoverlapping(
-                    s.as_ptr(),
-                    d.as_mut_ptr(),
-                    l);
+                d[..l].copy_from_slice(&s[..l]);
             }
 
             self.advance(l);
@@ -492,17 +1524,17 @@ impl<'a, T: Buf> Source for &'a mut T {
 }
 
 pub trait Sink {
-    fn sink<B: Buf>(&mut self, buf: &mut B);
+    fn sink<B: Buf + ?Sized>(&mut self, buf: &mut B);
 }
 
 impl Sink for [u8] {
-    fn sink<B: Buf>(&mut self, buf: &mut B) {
+    fn sink<B: Buf + ?Sized>(&mut self, buf: &mut B) {
         buf.read_slice(self);
     }
 }
 
 impl<T: MutBuf> Sink for T {
-    fn sink<B: Buf>(&mut self, buf: &mut B) {
+    fn sink<B: Buf + ?Sized>(&mut self, buf: &mut B) {
         Source::source(buf, self)
     }
 }
@@ -549,9 +1581,90 @@ impl<B: Buf + Sized> io::Read for Reader<B> {
     }
 }
 
+impl<B: Buf + Sized> io::BufRead for Reader<B> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        Ok(self.buf.bytes())
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.buf.advance(amt)
+    }
+}
+
+/// Seeking is relative to the start and end of the `SeekBuf`. Unlike
+/// `io::Cursor`, seeking past the end is an error.
+impl<B: SeekBuf + Sized> io::Seek for Reader<B> {
+    fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+        let cur = self.buf.position() as u64;
+        let len = cur + self.buf.remaining() as u64;
+
+        let target = match pos {
+            io::SeekFrom::Start(n) => Some(n),
+            io::SeekFrom::End(n) => offset(len, n),
+            io::SeekFrom::Current(n) => offset(cur, n),
+        };
+
+        match target {
+            Some(n) if n <= len => {
+                self.buf.set_position(n as usize);
+                Ok(n)
+            }
+            _ => Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                    "invalid seek to a negative or out of bounds position")),
+        }
+    }
+}
+
+fn offset(base: u64, n: i64) -> Option<u64> {
+    if n >= 0 {
+        base.checked_add(n as u64)
+    } else {
+        base.checked_sub(n.wrapping_neg() as u64)
+    }
+}
+
 /// Buffer related extension for `io::Read`
 pub trait ReadExt {
+    /// Reads into `buf` with a single vectored read, returning the number of
+    /// bytes read.
     fn read_buf<B: MutBuf>(&mut self, buf: &mut B) -> io::Result<usize>;
+
+    /// Reads exactly `n` bytes into `buf`, retrying reads that are
+    /// interrupted.
+    ///
+    /// If the reader would block after some bytes have been read, the number
+    /// of bytes read so far is returned and the caller should read the rest
+    /// once the reader is ready. An `UnexpectedEof` error is returned if the
+    /// reader reaches EOF first.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is greater than the remaining capacity of `buf`.
+    fn read_buf_exact<B: MutBuf>(&mut self, buf: &mut B, n: usize) -> io::Result<usize>;
+
+    /// Reads into `buf` until EOF, retrying reads that are interrupted, and
+    /// returns the number of bytes read.
+    ///
+    /// If the reader would block after some bytes have been read, the number
+    /// of bytes read so far is returned and the caller should call
+    /// `read_to_end_buf` again once the reader is ready; EOF has been reached
+    /// when it returns `Ok(0)`. `Vec<u8>` grows as needed and a `BlockBuf`
+    /// allocates blocks up to its maximum.
+    ///
+    /// Once `buf` is full, a single byte is read to check for EOF. If the
+    /// reader has more bytes, that byte is discarded and a `BufferFull` error
+    /// is returned.
+    ///
+    /// ```
+    /// use bytes::buf::{BlockBuf, ReadExt};
+    ///
+    /// let mut src: &[u8] = b"hello world";
+    /// let mut buf = BlockBuf::new(4, 4);
+    ///
+    /// assert_eq!(11, src.read_to_end_buf(&mut buf).unwrap());
+    /// assert_eq!(11, buf.len());
+    /// ```
+    fn read_to_end_buf<B: MutBuf>(&mut self, buf: &mut B) -> io::Result<usize>;
 }
 
 impl<T: io::Read> ReadExt for T {
@@ -560,24 +1673,195 @@ impl<T: io::Read> ReadExt for T {
             return Ok(0);
         }
 
-        unsafe {
-            let i = try!(self.read(buf.mut_bytes()));
+        read_vectored(self, buf, usize::MAX)
+    }
 
-            buf.advance(i);
-            Ok(i)
+    fn read_buf_exact<B: MutBuf>(&mut self, buf: &mut B, n: usize) -> io::Result<usize> {
+        assert!(n <= buf.remaining(), "buffer overflow");
+
+        buf.reserve(n);
+
+        let mut read = 0;
+
+        while read < n {
+            match read_vectored(self, buf, n - read) {
+                Ok(0) => {
+                    return Err(io::Error::new(io::ErrorKind::UnexpectedEof,
+                                              "failed to fill whole buffer"));
+                }
+                Ok(i) => read += i,
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(ref e) if e.kind() == io::ErrorKind::WouldBlock && read > 0 => {
+                    return Ok(read);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(read)
+    }
+
+    fn read_to_end_buf<B: MutBuf>(&mut self, buf: &mut B) -> io::Result<usize> {
+        let mut read = 0;
+
+        loop {
+            if !buf.has_remaining() {
+                // The source may end exactly where the buffer does, so probe
+                // for EOF before reporting that the buffer is full
+                match self.read(&mut [0]) {
+                    Ok(0) => return Ok(read),
+                    Ok(_) => return Err(io::Error::new(io::ErrorKind::Other, BufferFull)),
+                    Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(ref e) if e.kind() == io::ErrorKind::WouldBlock && read > 0 => {
+                        return Ok(read);
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+
+            match read_vectored(self, buf, usize::MAX) {
+                Ok(0) => return Ok(read),
+                Ok(i) => read += i,
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(ref e) if e.kind() == io::ErrorKind::WouldBlock && read > 0 => {
+                    return Ok(read);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+/// The maximum number of slices passed to a single vectored read
+const MAX_IOVECS: usize = 16;
+
+/// The maximum number of bytes that `MutBuf::init_bytes_vec` zeroes per call
+/// by default
+const READ_CHUNK_LEN: usize = 8 * 1024;
+
+/// Read at most `limit` bytes into `buf` with a single vectored read
+fn read_vectored<R, B>(src: &mut R, buf: &mut B, limit: usize) -> io::Result<usize>
+    where R: io::Read + ?Sized,
+          B: MutBuf + ?Sized,
+{
+    let i = {
+        // `io::Read` implementations may read from the destination, so the
+        // memory must be initialized.
+        let mut dst: [&mut [u8]; MAX_IOVECS] = Default::default();
+        let n = buf.init_bytes_vec(&mut dst, limit);
+        let mut iovecs = dst.map(|bytes| io::IoSliceMut::new(bytes));
+
+        src.read_vectored(&mut iovecs[..n])
+    };
+
+    let i = match i {
+        Ok(i) => i,
+        Err(e) => {
+            buf.release_unused();
+            return Err(e);
+        }
+    };
+
+    unsafe { buf.advance(i); }
+    Ok(i)
+}
+
+/// Fills `dst` with the leading `max` bytes of `slices`, skipping empty
+/// slices, and returns the number of slices that were filled.
+pub fn fill_slices<'a, I>(dst: &mut [&'a mut [u8]], slices: I, max: usize) -> usize
+    where I: IntoIterator<Item = &'a mut [u8]>,
+{
+    let mut rem = max;
+    let mut n = 0;
+
+    for bytes in slices {
+        if n == dst.len() || rem == 0 {
+            break;
+        }
+
+        if bytes.is_empty() {
+            continue;
         }
+
+        let len = cmp::min(bytes.len(), rem);
+        rem -= len;
+        dst[n] = &mut bytes[..len];
+        n += 1;
     }
+
+    n
 }
 
 /// Adapts a `MutBuf` to the `io::Write` trait
 pub struct Writer<B> {
     buf: B,
+    when_full: WhenFull,
+}
+
+/// What a `Writer` does when its `MutBuf` has no remaining capacity.
+///
+/// A partial write is not considered full: `write` returns the number of
+/// bytes that fit, as usual for `io::Write`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WhenFull {
+    /// Return an error of kind `io::ErrorKind::WouldBlock`. This is useful
+    /// when the buffer is drained by an event loop.
+    WouldBlock,
+    /// Return an error wrapping `BufferFull`
+    Error,
+    /// Reserve room for each write up front, so that growable buffers never
+    /// perform short writes. Buffers that cannot grow return an error
+    /// wrapping `BufferFull`.
+    Grow,
+}
+
+/// The error returned by a `Writer` when its `MutBuf` is full.
+///
+/// It is wrapped in an `io::Error` of kind `io::ErrorKind::Other`:
+///
+/// ```
+/// use bytes::MutBuf;
+/// use bytes::buf::BufferFull;
+/// use std::io::{Cursor, Write};
+///
+/// let mut dst = [0; 4];
+/// let mut writer = Cursor::new(&mut dst[..]).writer();
+///
+/// let err = writer.write_all(b"hello").unwrap_err();
+/// assert!(err.get_ref().unwrap().is::<BufferFull>());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BufferFull;
+
+impl fmt::Display for BufferFull {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("buffer full")
+    }
+}
+
+impl ::std::error::Error for BufferFull {
+    fn description(&self) -> &str {
+        "buffer full"
+    }
 }
 
 impl<B: MutBuf> Writer<B> {
-    /// Return a `Writer` for teh given `buf`
+    /// Return a `Writer` for the given `buf`, using `WhenFull::Grow`
     pub fn new(buf: B) -> Writer<B> {
-        Writer { buf: buf }
+        Writer {
+            buf: buf,
+            when_full: WhenFull::Grow,
+        }
+    }
+
+    /// Returns what the `Writer` does when the buffer is full
+    pub fn when_full(&self) -> WhenFull {
+        self.when_full
+    }
+
+    /// Sets what the `Writer` does when the buffer is full
+    pub fn set_when_full(&mut self, when_full: WhenFull) {
+        self.when_full = when_full
     }
 
     /// Gets a reference to the underlying buf.
@@ -598,14 +1882,29 @@ impl<B: MutBuf> Writer<B> {
 
 impl<B: MutBuf + Sized> io::Write for Writer<B> {
     fn write(&mut self, src: &[u8]) -> io::Result<usize> {
+        if self.when_full == WhenFull::Grow {
+            // Growable buffers make room for the entire slice up front, so
+            // the write is never short.
+            self.buf.reserve(src.len());
+        }
+
         let n = cmp::min(self.buf.remaining(), src.len());
 
+        if n == 0 && !src.is_empty() {
+            return Err(match self.when_full {
+                WhenFull::WouldBlock => io::ErrorKind::WouldBlock.into(),
+                WhenFull::Error | WhenFull::Grow => {
+                    io::Error::new(io::ErrorKind::Other, BufferFull)
+                }
+            });
+        }
+
         self.buf.copy_from(&src[0..n]);
         Ok(n)
     }
 
     fn flush(&mut self) -> io::Result<()> {
-        Ok(())
+        self.buf.flush_buf()
     }
 }
 
@@ -649,6 +1948,29 @@ impl<T: AsRef<[u8]>> Buf for io::Cursor<T> {
     }
 }
 
+impl<T: AsRef<[u8]>> SeekBuf for io::Cursor<T> {
+    fn position(&self) -> usize {
+        io::Cursor::position(self) as usize
+    }
+
+    fn set_position(&mut self, pos: usize) {
+        assert!(pos <= self.get_ref().as_ref().len(), "position out of bounds");
+        io::Cursor::set_position(self, pos as u64)
+    }
+}
+
+impl<T: AsRef<[u8]>> Checkpoint for io::Cursor<T> {
+    type Mark = u64;
+
+    fn checkpoint(&self) -> u64 {
+        io::Cursor::position(self)
+    }
+
+    fn rewind(&mut self, mark: u64) {
+        io::Cursor::set_position(self, mark)
+    }
+}
+
 impl<T: AsMut<[u8]> + AsRef<[u8]>> MutBuf for io::Cursor<T> {
 
     fn remaining(&self) -> usize {
@@ -662,64 +1984,272 @@ impl<T: AsMut<[u8]> + AsRef<[u8]>> MutBuf for io::Cursor<T> {
         self.set_position(pos as u64);
     }
 
-    /// Returns a mutable slice starting at the current MutBuf position and of
-    /// length between 0 and `MutBuf::remaining()`.
-    ///
-    /// The returned byte slice may represent uninitialized memory.
-    unsafe fn mut_bytes(&mut self) -> &mut [u8] {
+    fn mut_bytes(&mut self) -> &mut UninitSlice {
+        let pos = self.position() as usize;
+        UninitSlice::new(&mut (self.get_mut().as_mut())[pos..])
+    }
+
+    fn init_bytes_vec<'a>(&'a mut self, dst: &mut [&'a mut [u8]], max: usize) -> usize {
         let pos = self.position() as usize;
-        &mut (self.get_mut().as_mut())[pos..]
+        fill_slices(dst, Some(&mut (self.get_mut().as_mut())[pos..]), max)
     }
 }
 
 impl MutBuf for Vec<u8> {
     fn remaining(&self) -> usize {
-        usize::MAX - self.len()
+        MAX_CAPACITY - self.len()
+    }
+
+    fn is_bounded(&self) -> bool {
+        false
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        let len = self.len();
+
+        if self.capacity() - len < additional {
+            let required = len.checked_add(additional)
+                .expect("capacity overflow");
+
+            let cap = grow_capacity(self.capacity(), required);
+            self.reserve_exact(cap - len);
+        }
     }
 
     unsafe fn advance(&mut self, cnt: usize) {
         let len = self.len() + cnt;
 
-        if len > self.capacity() {
-            // Reserve additional
-            // TODO: Should this case panic?
-            let cap = self.capacity();
-            self.reserve(cap - len);
-        }
+        // `mut_bytes` only exposes the allocated capacity
+        assert!(len <= self.capacity(), "buffer overflow");
 
         self.set_len(len);
     }
 
-    unsafe fn mut_bytes(&mut self) -> &mut [u8] {
-        use std::slice;
-
+    fn mut_bytes(&mut self) -> &mut UninitSlice {
         if self.capacity() == self.len() {
-            self.reserve(64); // Grow the vec
+            MutBuf::reserve(self, MIN_GROW_CAPACITY);
         }
 
         let cap = self.capacity();
         let len = self.len();
 
-        let ptr = self.as_mut_ptr();
-        &mut slice::from_raw_parts_mut(ptr, cap)[len..]
+        unsafe {
+            let ptr = self.as_mut_ptr().offset(len as isize);
+            UninitSlice::from_raw_parts_mut(ptr, cap - len)
+        }
+    }
+}
+
+/*
+ *
+ * ===== Shared reads =====
+ *
+ */
+
+/// Read `len` bytes from `buf`, sharing memory with it where possible
+pub fn read_shared<B: Buf + ?Sized>(buf: &mut B, len: usize) -> Bytes {
+    let first = match buf.bytes_shared() {
+        Some(bytes) => bytes,
+        None => {
+            let mut vec = vec![0; len];
+            buf.read_slice(&mut vec);
+            return Bytes::from(vec);
+        }
+    };
+
+    if first.len() >= len {
+        buf.advance(len);
+        return first.slice_to(len);
+    }
+
+    let mut builder = BytesBuilder::new();
+    let mut rem = len;
+    let mut chunk = first;
+
+    loop {
+        let n = chunk.len();
+        builder.push(chunk);
+        buf.advance(n);
+        rem -= n;
+
+        if rem == 0 {
+            return builder.build();
+        }
+
+        chunk = buf.bytes_shared()
+            .unwrap_or_else(|| Bytes::from_slice(buf.bytes()));
+
+        if chunk.len() > rem {
+            chunk = chunk.slice_to(rem);
+        }
     }
 }
 
+/*
+ *
+ * ===== Growth =====
+ *
+ */
+
+/// The maximum number of bytes a growable buffer may hold
+pub const MAX_CAPACITY: usize = isize::MAX as usize;
+
+/// The smallest allocation made when a growable buffer runs out of capacity
+const MIN_GROW_CAPACITY: usize = 64;
+
+/// Returns the capacity that a growable buffer of capacity `cap` should grow
+/// to in order to hold at least `required` bytes.
+///
+/// The capacity is at least doubled, so that repeatedly writing to a growable
+/// buffer takes amortized constant time per byte.
+pub fn grow_capacity(cap: usize, required: usize) -> usize {
+    assert!(required <= MAX_CAPACITY, "capacity overflow");
+
+    let doubled = cmp::min(cap.saturating_mul(2), MAX_CAPACITY);
+    cmp::max(required, cmp::max(doubled, MIN_GROW_CAPACITY))
+}
+
 /*
  *
  * ===== fmt impls =====
  *
  */
 
-pub struct Fmt<'a, B: 'a>(pub &'a mut B);
+/// Formatting extension for `MutBuf`
+///
+/// This is a separate trait so that it does not conflict with
+/// `io::Write::write_fmt` for types such as `Vec<u8>` that implement both.
+pub trait FmtExt: MutBuf {
+    /// Writes formatted data to the `MutBuf`, which allows using the `write!`
+    /// macro directly on a buffer.
+    ///
+    /// Unlike `write_str`, this does not panic when the buffer is full. The
+    /// output is truncated and `fmt::Error` is returned instead.
+    ///
+    /// ```
+    /// use bytes::buf::FmtExt;
+    ///
+    /// let mut buf = vec![];
+    /// write!(buf, "{}-{}", 1, 2).unwrap();
+    ///
+    /// assert_eq!(buf, b"1-2");
+    /// ```
+    fn write_fmt(&mut self, args: fmt::Arguments) -> fmt::Result;
+}
+
+impl<T: MutBuf + ?Sized> FmtExt for T {
+    fn write_fmt(&mut self, args: fmt::Arguments) -> fmt::Result {
+        fmt::Write::write_fmt(&mut Fmt(self), args)
+    }
+}
+
+/// Adapts a `MutBuf` to the `fmt::Write` trait
+///
+/// Output that does not fit in the buffer is truncated and `fmt::Error` is
+/// returned. Use `TruncatingFmt` to find out how many bytes were dropped.
+pub struct Fmt<'a, B: ?Sized + 'a>(pub &'a mut B);
 
-impl<'a, B: MutBuf> fmt::Write for Fmt<'a, B> {
+impl<'a, B: MutBuf + ?Sized> fmt::Write for Fmt<'a, B> {
     fn write_str(&mut self, s: &str) -> fmt::Result {
-        self.0.write_str(s);
-        Ok(())
+        if self.0.put_slice(s.as_bytes()) < s.len() {
+            Err(fmt::Error)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Adapts a `MutBuf` to the `fmt::Write` trait, counting the bytes that do
+/// not fit in the buffer
+///
+/// Like `Fmt`, output is truncated when the buffer is full and `fmt::Error`
+/// is returned. Formatting continues to the end of the arguments so that the
+/// number of bytes that were dropped is available from `truncated`.
+///
+/// This is a separate type because `Fmt` is a tuple struct with a single
+/// public field, constructed as `Fmt(&mut buf)`. Adding the counter to `Fmt`
+/// would break that constructor.
+///
+/// ```
+/// use bytes::buf::{TruncatingFmt, SliceBuf};
+/// use std::fmt::Write;
+///
+/// let mut buf = SliceBuf::with_capacity(8);
+///
+/// {
+///     let mut fmt = TruncatingFmt::new(&mut buf);
+///
+///     assert!(write!(fmt, "hello {}", "world").is_err());
+///     assert_eq!(3, fmt.truncated());
+/// }
+///
+/// assert_eq!(b"hello wo", bytes::Buf::bytes(&buf));
+/// ```
+pub struct TruncatingFmt<'a, B: ?Sized + 'a> {
+    buf: &'a mut B,
+    truncated: usize,
+}
+
+impl<'a, B: MutBuf + ?Sized> TruncatingFmt<'a, B> {
+    /// Return a `TruncatingFmt` writing to the given `buf`
+    pub fn new(buf: &'a mut B) -> TruncatingFmt<'a, B> {
+        TruncatingFmt {
+            buf: buf,
+            truncated: 0,
+        }
+    }
+
+    /// Returns the number of bytes that did not fit in the buffer
+    pub fn truncated(&self) -> usize {
+        self.truncated
+    }
+
+    /// Gets a reference to the underlying buf.
+    pub fn get_ref(&self) -> &B {
+        self.buf
+    }
+
+    /// Gets a mutable reference to the underlying buf.
+    pub fn get_mut(&mut self) -> &mut B {
+        self.buf
+    }
+
+    // Writes as much of `s` as fits, counting the bytes that do not.
+    fn write_truncated(&mut self, s: &str) {
+        let n = self.buf.put_slice(s.as_bytes());
+        self.truncated += s.len() - n;
+    }
+}
+
+impl<'a, B: MutBuf + ?Sized> fmt::Write for TruncatingFmt<'a, B> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.write_truncated(s);
+
+        if self.truncated > 0 {
+            Err(fmt::Error)
+        } else {
+            Ok(())
+        }
     }
 
     fn write_fmt(&mut self, args: fmt::Arguments) -> fmt::Result {
-        fmt::write(self, args)
+        // Keep formatting after the buffer is full so that all of the
+        // truncated bytes are counted.
+        struct Counting<'b, 'a: 'b, B: ?Sized + 'a>(&'b mut TruncatingFmt<'a, B>);
+
+        impl<'b, 'a, B: MutBuf + ?Sized> fmt::Write for Counting<'b, 'a, B> {
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                self.0.write_truncated(s);
+                Ok(())
+            }
+        }
+
+        try!(fmt::write(&mut Counting(self), args));
+
+        if self.truncated > 0 {
+            Err(fmt::Error)
+        } else {
+            Ok(())
+        }
     }
 }
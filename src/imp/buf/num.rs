@@ -0,0 +1,108 @@
+//! Encoding of integer and floating point types that `byteorder` does not
+//! support directly.
+
+use byteorder::ByteOrder;
+
+/// Returns true if `T` is big-endian
+pub fn is_big_endian<T: ByteOrder>() -> bool {
+    T::read_u16(&[0, 1]) == 1
+}
+
+pub fn read_u128<T: ByteOrder>(buf: &[u8]) -> u128 {
+    let (hi, lo) = if is_big_endian::<T>() {
+        (&buf[..8], &buf[8..16])
+    } else {
+        (&buf[8..16], &buf[..8])
+    };
+
+    ((T::read_u64(hi) as u128) << 64) | T::read_u64(lo) as u128
+}
+
+pub fn write_u128<T: ByteOrder>(buf: &mut [u8], n: u128) {
+    let hi = (n >> 64) as u64;
+    let lo = n as u64;
+
+    if is_big_endian::<T>() {
+        T::write_u64(&mut buf[..8], hi);
+        T::write_u64(&mut buf[8..16], lo);
+    } else {
+        T::write_u64(&mut buf[..8], lo);
+        T::write_u64(&mut buf[8..16], hi);
+    }
+}
+
+/// Convert the bits of an IEEE754 half-precision float to an `f32`. The
+/// conversion is exact.
+pub fn f16_to_f32(h: u16) -> f32 {
+    let sign = ((h & 0x8000) as u32) << 16;
+    let exp = ((h >> 10) & 0x1F) as u32;
+    let man = (h & 0x3FF) as u32;
+
+    let bits = match exp {
+        // Zero and subnormals, which are `man * 2^-24` and are all normal
+        // numbers in single precision
+        0 => {
+            let val = man as f32 / (1 << 24) as f32;
+            return f32::from_bits(sign | val.to_bits());
+        }
+        // Infinity and NaN, preserving the NaN payload
+        0x1F => sign | 0x7F80_0000 | (man << 13),
+        _ => sign | ((exp + 127 - 15) << 23) | (man << 13),
+    };
+
+    f32::from_bits(bits)
+}
+
+/// Convert an `f32` to the bits of the nearest IEEE754 half-precision float,
+/// rounding ties to even. Values too large to represent become infinity.
+pub fn f32_to_f16(n: f32) -> u16 {
+    let x = n.to_bits();
+    let sign = ((x >> 16) & 0x8000) as u16;
+    let exp = ((x >> 23) & 0xFF) as i32;
+    let man = x & 0x7F_FFFF;
+
+    if exp == 0xFF {
+        if man == 0 {
+            return sign | 0x7C00;
+        }
+
+        // Keep the NaN quiet, even if the payload is truncated away
+        return sign | 0x7E00 | (man >> 13) as u16;
+    }
+
+    let half_exp = exp - 127 + 15;
+
+    if half_exp >= 0x1F {
+        return sign | 0x7C00;
+    }
+
+    if half_exp <= 0 {
+        // Less than half of the smallest subnormal always rounds to zero
+        if half_exp < -10 {
+            return sign;
+        }
+
+        let man = man | 0x80_0000;
+        let shift = (14 - half_exp) as u32;
+
+        return sign | round(man, shift) as u16;
+    }
+
+    // A carry out of the mantissa correctly increments the exponent, up to
+    // infinity
+    let bits = ((half_exp as u32) << 23) | man;
+    sign | round(bits, 13) as u16
+}
+
+/// Shift `n` right by `shift` bits, rounding to nearest with ties to even
+fn round(n: u32, shift: u32) -> u32 {
+    let ret = n >> shift;
+    let rem = n & ((1 << shift) - 1);
+    let half = 1 << (shift - 1);
+
+    if rem > half || (rem == half && ret & 1 == 1) {
+        ret + 1
+    } else {
+        ret
+    }
+}
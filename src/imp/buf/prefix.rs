@@ -0,0 +1,325 @@
+//! Length prefixes and backpatching of written bytes.
+
+use {Buf, MutBuf, Bytes};
+use imp::buf::read_shared;
+use std::{error, fmt};
+
+/// The encoding of a length prefix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LengthPrefix {
+    /// A single byte
+    U8,
+    /// An unsigned 16 bit integer in big-endian byte order
+    U16BE,
+    /// An unsigned 16 bit integer in little-endian byte order
+    U16LE,
+    /// An unsigned 32 bit integer in big-endian byte order
+    U32BE,
+    /// An unsigned 32 bit integer in little-endian byte order
+    U32LE,
+    /// An unsigned LEB128 variable length integer.
+    ///
+    /// As the length is not known when a placeholder is reserved, placeholder
+    /// varints are always padded to 5 bytes, which is enough to hold any `u32`.
+    Varint,
+}
+
+/// An error returned when reading a length-prefixed value from a `Buf`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrefixError {
+    /// The `Buf` ended before the end of the prefix or the payload
+    Incomplete,
+    /// The length is larger than the maximum allowed by the caller
+    TooLong,
+    /// A varint prefix is longer than 5 bytes or does not fit in a `u32`
+    InvalidVarint,
+    /// The payload is not valid UTF-8
+    InvalidUtf8,
+}
+
+impl PrefixError {
+    fn as_str(&self) -> &'static str {
+        match *self {
+            PrefixError::Incomplete => "buffer ended before the end of a length-prefixed value",
+            PrefixError::TooLong => "length-prefixed value is too long",
+            PrefixError::InvalidVarint => "invalid varint length prefix",
+            PrefixError::InvalidUtf8 => "length-prefixed value is not valid UTF-8",
+        }
+    }
+}
+
+impl fmt::Display for PrefixError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str(self.as_str())
+    }
+}
+
+impl error::Error for PrefixError {
+    fn description(&self) -> &str {
+        self.as_str()
+    }
+}
+
+/// The location of a length prefix placeholder, returned by
+/// `Patch::reserve_prefix`.
+#[must_use = "the placeholder must be filled in with `Patch::fill_prefix`"]
+#[derive(Debug)]
+pub struct PrefixMark {
+    pos: usize,
+    kind: LengthPrefix,
+}
+
+/// A `MutBuf` that allows bytes that have already been written to be
+/// overwritten.
+///
+/// This is used to write length-prefixed frames whose length is not known up
+/// front: reserve a placeholder for the prefix, write the body, then fill in
+/// the placeholder with the number of bytes written.
+///
+/// ```
+/// use bytes::MutBuf;
+/// use bytes::buf::{Patch, LengthPrefix};
+///
+/// let mut buf = vec![];
+///
+/// let mark = buf.reserve_prefix(LengthPrefix::U16BE);
+/// buf.write_slice(b"hello");
+/// assert_eq!(5, buf.fill_prefix(mark));
+///
+/// assert_eq!(buf, b"\x00\x05hello");
+/// ```
+pub trait Patch: MutBuf {
+    /// Returns the position at which the next byte will be written.
+    ///
+    /// Positions are only meaningful to `patch` and increase by one for each
+    /// byte written.
+    fn write_position(&self) -> usize;
+
+    /// Overwrite previously written bytes, starting at `pos`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the bytes to overwrite have not been written yet or
+    /// are no longer held by the buffer.
+    fn patch(&mut self, pos: usize, src: &[u8]);
+
+    /// Write a placeholder for a length prefix of the given kind.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is not enough remaining capacity for the placeholder.
+    fn reserve_prefix(&mut self, kind: LengthPrefix) -> PrefixMark {
+        let pos = self.write_position();
+        self.put_bytes(0, kind.placeholder_len());
+
+        PrefixMark {
+            pos: pos,
+            kind: kind,
+        }
+    }
+
+    /// Fill in a placeholder with the number of bytes written since it was
+    /// reserved. Returns that number.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of bytes is too large to be represented by the
+    /// prefix.
+    fn fill_prefix(&mut self, mark: PrefixMark) -> usize {
+        let start = mark.pos + mark.kind.placeholder_len();
+        let len = self.write_position() - start;
+
+        let mut buf = [0; MAX_PLACEHOLDER_LEN];
+        let n = mark.kind.encode_placeholder(len, &mut buf);
+
+        self.patch(mark.pos, &buf[..n]);
+        len
+    }
+}
+
+const VARINT_PLACEHOLDER_LEN: usize = 5;
+const MAX_PLACEHOLDER_LEN: usize = 5;
+
+impl LengthPrefix {
+    /// Returns the number of bytes in a placeholder for this prefix
+    pub fn placeholder_len(&self) -> usize {
+        match *self {
+            LengthPrefix::U8 => 1,
+            LengthPrefix::U16BE | LengthPrefix::U16LE => 2,
+            LengthPrefix::U32BE | LengthPrefix::U32LE => 4,
+            LengthPrefix::Varint => VARINT_PLACEHOLDER_LEN,
+        }
+    }
+
+    /// Returns the largest length a placeholder for this prefix can hold
+    pub fn max_len(&self) -> usize {
+        match *self {
+            LengthPrefix::U8 => 0xFF,
+            LengthPrefix::U16BE | LengthPrefix::U16LE => 0xFFFF,
+            LengthPrefix::U32BE | LengthPrefix::U32LE => 0xFFFF_FFFF,
+            LengthPrefix::Varint => 0xFFFF_FFFF,
+        }
+    }
+
+    /// Encode `len` using as few bytes as possible, returning the number of
+    /// bytes used.
+    fn encode(&self, len: usize, dst: &mut [u8]) -> usize {
+        if *self != LengthPrefix::Varint {
+            return self.encode_placeholder(len, dst);
+        }
+
+        assert!(len as u64 <= self.max_len() as u64,
+                "length {} does not fit in a {:?} prefix", len, self);
+
+        let mut rem = len;
+        let mut i = 0;
+
+        loop {
+            let byte = (rem & 0x7F) as u8;
+            rem >>= 7;
+
+            if rem == 0 {
+                dst[i] = byte;
+                return i + 1;
+            }
+
+            dst[i] = byte | 0x80;
+            i += 1;
+        }
+    }
+
+    /// Encode `len` into the fixed size placeholder representation, returning
+    /// the number of bytes used.
+    fn encode_placeholder(&self, len: usize, dst: &mut [u8]) -> usize {
+        use byteorder::{ByteOrder, BigEndian, LittleEndian};
+
+        assert!(len as u64 <= self.max_len() as u64,
+                "length {} does not fit in a {:?} prefix", len, self);
+
+        match *self {
+            LengthPrefix::U8 => dst[0] = len as u8,
+            LengthPrefix::U16BE => BigEndian::write_u16(dst, len as u16),
+            LengthPrefix::U16LE => LittleEndian::write_u16(dst, len as u16),
+            LengthPrefix::U32BE => BigEndian::write_u32(dst, len as u32),
+            LengthPrefix::U32LE => LittleEndian::write_u32(dst, len as u32),
+            LengthPrefix::Varint => {
+                // Every byte but the last has the continuation bit set, even
+                // if the remaining bits are zero.
+                for i in 0..VARINT_PLACEHOLDER_LEN {
+                    let mut byte = ((len >> (7 * i)) & 0x7F) as u8;
+
+                    if i < VARINT_PLACEHOLDER_LEN - 1 {
+                        byte |= 0x80;
+                    }
+
+                    dst[i] = byte;
+                }
+            }
+        }
+
+        self.placeholder_len()
+    }
+
+    /// Decode a prefix of this kind from the front of `buf` without
+    /// consuming it. Returns the length and the size of the prefix.
+    fn decode<B: Buf + ?Sized>(&self, buf: &B) -> Result<(usize, usize), PrefixError> {
+        use byteorder::{ByteOrder, BigEndian, LittleEndian};
+
+        let mut head = [0; MAX_PLACEHOLDER_LEN];
+        let n = buf.peek_slice(&mut head);
+        let head = &head[..n];
+
+        if *self == LengthPrefix::Varint {
+            return decode_varint(head);
+        }
+
+        if n < self.placeholder_len() {
+            return Err(PrefixError::Incomplete);
+        }
+
+        let len = match *self {
+            LengthPrefix::U8 => head[0] as u64,
+            LengthPrefix::U16BE => BigEndian::read_u16(head) as u64,
+            LengthPrefix::U16LE => LittleEndian::read_u16(head) as u64,
+            LengthPrefix::U32BE => BigEndian::read_u32(head) as u64,
+            LengthPrefix::U32LE => LittleEndian::read_u32(head) as u64,
+            LengthPrefix::Varint => unreachable!(),
+        };
+
+        // A `u32` length may not fit in a 16 bit `usize`
+        if len > usize::MAX as u64 {
+            return Err(PrefixError::TooLong);
+        }
+
+        Ok((len as usize, self.placeholder_len()))
+    }
+}
+
+/// Decode an unsigned LEB128 varint of at most 5 bytes, including the padded
+/// encoding written by `Patch::fill_prefix`. Returns the value and the number
+/// of bytes it is encoded with.
+fn decode_varint(src: &[u8]) -> Result<(usize, usize), PrefixError> {
+    let mut ret: u64 = 0;
+
+    for (i, &byte) in src.iter().enumerate() {
+        ret |= ((byte & 0x7F) as u64) << (7 * i);
+
+        if byte & 0x80 == 0 {
+            if ret > LengthPrefix::Varint.max_len() as u64 {
+                return Err(PrefixError::InvalidVarint);
+            }
+
+            return Ok((ret as usize, i + 1));
+        }
+    }
+
+    if src.len() < VARINT_PLACEHOLDER_LEN {
+        Err(PrefixError::Incomplete)
+    } else {
+        Err(PrefixError::InvalidVarint)
+    }
+}
+
+/// Read a length prefix of the given kind followed by that many bytes. See
+/// `Buf::read_prefixed_bytes`.
+///
+/// The prefix is only consumed along with the payload, so nothing is
+/// consumed if an error is returned.
+pub fn read_prefixed<B: Buf + ?Sized>(buf: &mut B, kind: LengthPrefix, max_len: usize)
+        -> Result<Bytes, PrefixError> {
+    let (len, n) = try!(kind.decode(buf));
+
+    if len > max_len {
+        return Err(PrefixError::TooLong);
+    }
+
+    if buf.remaining() - n < len {
+        return Err(PrefixError::Incomplete);
+    }
+
+    buf.advance(n);
+    Ok(read_shared(buf, len))
+}
+
+/// Write a length prefix of the given kind followed by `src`. See
+/// `MutBuf::write_prefixed`.
+pub fn write_prefixed<B: MutBuf + ?Sized>(buf: &mut B, kind: LengthPrefix, src: &[u8]) {
+    let mut prefix = [0; MAX_PLACEHOLDER_LEN];
+    let n = kind.encode(src.len(), &mut prefix);
+
+    assert!(buf.remaining() >= n + src.len(), "buffer overflow");
+
+    buf.write_slice(&prefix[..n]);
+    buf.write_slice(src);
+}
+
+impl Patch for Vec<u8> {
+    fn write_position(&self) -> usize {
+        self.len()
+    }
+
+    fn patch(&mut self, pos: usize, src: &[u8]) {
+        assert!(pos + src.len() <= self.len(), "patch out of bounds");
+        self[pos..pos + src.len()].copy_from_slice(src);
+    }
+}
@@ -1,6 +1,8 @@
 use {Buf, MutBuf};
+use buf::{Checkpoint, UninitSlice};
 use imp::alloc;
-use std::fmt;
+use imp::buf::fill_slices;
+use std::{cmp, fmt};
 
 
 /// `RingBuf` is backed by contiguous memory and writes may wrap.
@@ -21,7 +23,7 @@ pub struct RingBuf<T = Box<[u8]>> {
 impl RingBuf {
     /// Allocates a new `RingBuf` with the specified capacity.
     pub fn with_capacity(capacity: usize) -> RingBuf {
-        let mem = unsafe { alloc::with_capacity(capacity) };
+        let mem = alloc::with_capacity(capacity);
         RingBuf::new(mem)
     }
 }
@@ -126,6 +128,33 @@ impl<T: AsRef<[u8]>> Buf for RingBuf<T> {
         assert!(cnt <= self.remaining_read(), "buffer overflow");
         self.rd += cnt as u64
     }
+
+    /// Copies the bytes before and after the end of the memory is wrapped
+    /// around
+    fn peek_slice(&self, dst: &mut [u8]) -> usize {
+        let mem = self.mem.as_ref();
+        let n = cmp::min(dst.len(), self.remaining_read());
+        let a = (self.rd & self.mask) as usize;
+        let tail = cmp::min(n, mem.len() - a);
+
+        dst[..tail].copy_from_slice(&mem[a..a + tail]);
+        dst[tail..n].copy_from_slice(&mem[..n - tail]);
+        n
+    }
+}
+
+/// Rewinding panics if the bytes following the checkpoint have been
+/// overwritten since.
+impl<T: AsRef<[u8]>> Checkpoint for RingBuf<T> {
+    type Mark = u64;
+
+    fn checkpoint(&self) -> u64 {
+        self.rd
+    }
+
+    fn rewind(&mut self, mark: u64) {
+        self.set_position(mark)
+    }
 }
 
 impl<T> MutBuf for RingBuf<T>
@@ -140,19 +169,45 @@ impl<T> MutBuf for RingBuf<T>
         self.wr += cnt as u64;
     }
 
-    unsafe fn mut_bytes(&mut self) -> &mut [u8] {
+    fn mut_bytes(&mut self) -> &mut UninitSlice {
+        let rem = self.remaining_write();
         let a = (self.wr & self.mask) as usize;
+        let n = cmp::min(rem, self.capacity() - a);
 
-        if self.wr > self.rd {
-            let b = (self.rd & self.mask) as usize;
+        UninitSlice::new(&mut self.mem.as_mut()[a..a + n])
+    }
 
-            if a >= b {
-                &mut self.mem.as_mut()[a..]
-            } else {
-                &mut self.mem.as_mut()[a..b]
-            }
-        } else {
-            &mut self.mem.as_mut()[a..]
+    /// Returns both halves of the free space when it wraps around the end of
+    /// the memory.
+    fn mut_bytes_vec<'a>(&'a mut self, dst: &mut [&'a mut UninitSlice]) -> usize {
+        let rem = self.remaining_write();
+
+        if dst.is_empty() || rem == 0 {
+            return 0;
+        }
+
+        let a = (self.wr & self.mask) as usize;
+        let n = cmp::min(rem, self.capacity() - a);
+        let (head, tail) = self.mem.as_mut().split_at_mut(a);
+
+        dst[0] = UninitSlice::new(&mut tail[..n]);
+
+        if n == rem || dst.len() == 1 {
+            return 1;
         }
+
+        dst[1] = UninitSlice::new(&mut head[..rem - n]);
+        2
+    }
+
+    fn init_bytes_vec<'a>(&'a mut self, dst: &mut [&'a mut [u8]], max: usize) -> usize {
+        let rem = self.remaining_write();
+        let a = (self.wr & self.mask) as usize;
+        let n = cmp::min(rem, self.capacity() - a);
+        let (head, tail) = self.mem.as_mut().split_at_mut(a);
+
+        let slices = Some(&mut tail[..n]).into_iter().chain(Some(&mut head[..rem - n]));
+
+        fill_slices(dst, slices, max)
     }
 }
@@ -1,7 +1,9 @@
 //! A buffer backed by a contiguous region of memory.
 
 use {Buf, MutBuf};
+use buf::{Checkpoint, Patch, SeekBuf, UninitSlice};
 use imp::alloc;
+use imp::buf::{fill_slices, grow_capacity, MAX_CAPACITY};
 use std::fmt;
 
 /*
@@ -14,6 +16,9 @@ use std::fmt;
 ///
 /// This `Buf` is better suited for cases where there is a clear delineation
 /// between reading and writing.
+///
+/// A `SliceBuf` created with `SliceBuf::growable` reallocates its memory when
+/// writing past its capacity.
 pub struct SliceBuf<T = Box<[u8]>> {
     // Contiguous memory
     mem: T,
@@ -21,6 +26,8 @@ pub struct SliceBuf<T = Box<[u8]>> {
     rd: usize,
     // Current write position
     wr: usize,
+    // Reallocates the memory to the given capacity, if the buffer is growable
+    grow: Option<fn(&mut T, usize)>,
 }
 
 impl SliceBuf {
@@ -28,10 +35,20 @@ impl SliceBuf {
     ///
     /// The `SliceBuf` will be backed by a `Box<[u8]>`.
     pub fn with_capacity(capacity: usize) -> SliceBuf {
-        let mem = unsafe { alloc::with_capacity(capacity) };
+        let mem = alloc::with_capacity(capacity);
         SliceBuf::new(mem)
     }
 
+    /// Constructs a new, empty `SliceBuf` with the specified initial capacity
+    /// that grows as it is written to.
+    ///
+    /// The capacity at least doubles each time the buffer grows.
+    pub fn growable(capacity: usize) -> SliceBuf {
+        let mut buf = SliceBuf::with_capacity(capacity);
+        buf.grow = Some(grow_boxed);
+        buf
+    }
+
     /// Create a new `SliceBuf` and copy the contents of the given slice into
     /// it.
     pub fn from_slice<T: AsRef<[u8]>>(bytes: &T) -> SliceBuf {
@@ -48,6 +65,7 @@ impl<T: AsRef<[u8]>> SliceBuf<T> {
             mem: mem,
             rd: 0,
             wr: 0,
+            grow: None,
         }
     }
 
@@ -89,9 +107,29 @@ impl<T: AsRef<[u8]>> SliceBuf<T> {
     }
 
     /// Return the remaining write capacity
+    ///
+    /// For growable buffers, this is the capacity left before the memory is
+    /// reallocated.
     pub fn remaining_write(&self) -> usize {
         self.capacity() - self.wr
     }
+
+    /// Returns true if the buffer reallocates its memory when full
+    pub fn is_growable(&self) -> bool {
+        self.grow.is_some()
+    }
+}
+
+impl<T: AsRef<[u8]> + AsMut<[u8]>> SliceBuf<T> {
+    /// Moves the bytes left to read to the start of the buffer, making room
+    /// to write after them. The read position becomes 0.
+    pub fn compact(&mut self) {
+        let len = self.remaining_read();
+
+        self.mem.as_mut().copy_within(self.rd..self.wr, 0);
+        self.rd = 0;
+        self.wr = len;
+    }
 }
 
 impl<T> Buf for SliceBuf<T>
@@ -119,11 +157,57 @@ impl<T> Buf for SliceBuf<T>
     }
 }
 
+impl<T> SeekBuf for SliceBuf<T>
+    where T: AsRef<[u8]>,
+{
+    fn position(&self) -> usize {
+        SliceBuf::position(self)
+    }
+
+    fn set_position(&mut self, pos: usize) {
+        SliceBuf::set_position(self, pos)
+    }
+}
+
+impl<T> Checkpoint for SliceBuf<T>
+    where T: AsRef<[u8]>,
+{
+    type Mark = usize;
+
+    fn checkpoint(&self) -> usize {
+        self.rd
+    }
+
+    fn rewind(&mut self, mark: usize) {
+        self.set_position(mark)
+    }
+}
+
 impl<T> MutBuf for SliceBuf<T>
     where T: AsRef<[u8]> + AsMut<[u8]>,
 {
     fn remaining(&self) -> usize {
-        self.remaining_write()
+        if self.is_growable() {
+            MAX_CAPACITY - self.wr
+        } else {
+            self.remaining_write()
+        }
+    }
+
+    fn is_bounded(&self) -> bool {
+        !self.is_growable()
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        if let Some(grow) = self.grow {
+            if self.remaining_write() < additional {
+                let required = self.wr.checked_add(additional)
+                    .expect("capacity overflow");
+
+                let cap = grow_capacity(self.capacity(), required);
+                grow(&mut self.mem, cap);
+            }
+        }
     }
 
     unsafe fn advance(&mut self, cnt: usize) {
@@ -131,11 +215,25 @@ impl<T> MutBuf for SliceBuf<T>
         self.wr += cnt;
     }
 
-    unsafe fn mut_bytes(&mut self) -> &mut [u8] {
-        &mut self.mem.as_mut()[self.wr..]
+    fn mut_bytes(&mut self) -> &mut UninitSlice {
+        if self.remaining_write() == 0 {
+            self.reserve(1);
+        }
+
+        UninitSlice::new(&mut self.mem.as_mut()[self.wr..])
+    }
+
+    fn init_bytes_vec<'a>(&'a mut self, dst: &mut [&'a mut [u8]], max: usize) -> usize {
+        if self.remaining_write() == 0 {
+            self.reserve(1);
+        }
+
+        fill_slices(dst, Some(&mut self.mem.as_mut()[self.wr..]), max)
     }
 
     fn write_slice(&mut self, src: &[u8]) {
+        self.reserve(src.len());
+
         let wr = self.wr;
 
         self.mem.as_mut()[wr..wr+src.len()]
@@ -145,16 +243,36 @@ impl<T> MutBuf for SliceBuf<T>
     }
 }
 
+impl<T> Patch for SliceBuf<T>
+    where T: AsRef<[u8]> + AsMut<[u8]>,
+{
+    fn write_position(&self) -> usize {
+        self.wr
+    }
+
+    fn patch(&mut self, pos: usize, src: &[u8]) {
+        assert!(pos + src.len() <= self.wr, "patch out of bounds");
+        self.mem.as_mut()[pos..pos + src.len()].copy_from_slice(src);
+    }
+}
+
 impl Clone for SliceBuf {
     fn clone(&self) -> Self {
         SliceBuf {
             mem: self.mem.clone(),
             rd: self.rd,
             wr: self.wr,
+            grow: self.grow,
         }
     }
 }
 
+fn grow_boxed(mem: &mut Box<[u8]>, cap: usize) {
+    let mut new_mem = alloc::with_capacity(cap);
+    new_mem[..mem.len()].copy_from_slice(mem);
+    *mem = new_mem;
+}
+
 impl<T> fmt::Debug for SliceBuf<T>
     where T: AsRef<[u8]>,
 {
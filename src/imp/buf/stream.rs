@@ -0,0 +1,379 @@
+//! Buffers backed by `io::Read` and `io::Write` streams.
+
+use {Buf, MutBuf};
+use buf::{ReadExt, SliceBuf, UninitSlice};
+use imp::alloc;
+use imp::buf::{fill_slices, MAX_CAPACITY};
+use std::{cmp, fmt, io};
+
+/// The default capacity of the internal buffer
+const DEFAULT_CAPACITY: usize = 8 * 1024;
+
+/*
+ *
+ * ===== ReadBuf =====
+ *
+ */
+
+/// A `Buf` over bytes read from an `io::Read` source.
+///
+/// The bytes are read into an internal growable `SliceBuf`. The `Buf`
+/// implementation only covers the bytes that are already buffered: `Buf`
+/// methods never read from the source. Reading more than `remaining()` bytes
+/// panics like with any other `Buf`, even if the source has more data, so
+/// call `fill(n)` before each read of `n` bytes, for example before decoding
+/// a fixed size header.
+///
+/// ```
+/// extern crate bytes;
+/// extern crate byteorder;
+///
+/// use bytes::{Buf, BufExt};
+/// use bytes::buf::ReadBuf;
+/// use byteorder::BigEndian;
+///
+/// # fn main() {
+/// let src: &[u8] = b"\x00\x00\x00\x05hello";
+/// let mut buf = ReadBuf::new(src);
+///
+/// buf.fill(4).unwrap();
+/// let len = buf.read_u32::<BigEndian>() as usize;
+///
+/// buf.fill(len).unwrap();
+/// assert_eq!(&buf.bytes()[..len], b"hello");
+/// # }
+/// ```
+pub struct ReadBuf<R> {
+    inner: R,
+    buf: SliceBuf,
+}
+
+impl<R: io::Read> ReadBuf<R> {
+    /// Create a new `ReadBuf` with a default capacity of 8 KiB
+    pub fn new(inner: R) -> ReadBuf<R> {
+        ReadBuf::with_capacity(DEFAULT_CAPACITY, inner)
+    }
+
+    /// Create a new `ReadBuf` with the specified initial capacity
+    pub fn with_capacity(capacity: usize, inner: R) -> ReadBuf<R> {
+        ReadBuf {
+            inner: inner,
+            buf: SliceBuf::growable(capacity),
+        }
+    }
+
+    /// Reads from the source until at least `min` bytes are buffered, and
+    /// returns the number of buffered bytes.
+    ///
+    /// The buffered bytes are contiguous, so `bytes` returns a slice of at
+    /// least `min` bytes afterwards. The internal buffer grows if it is
+    /// smaller than `min`. Interrupted reads are retried.
+    ///
+    /// Fewer than `min` bytes are buffered only if the source reached EOF.
+    /// If the source returns an error, bytes read before the error remain
+    /// buffered.
+    pub fn fill(&mut self, min: usize) -> io::Result<usize> {
+        let len = self.buf.remaining_read();
+
+        if len >= min {
+            return Ok(len);
+        }
+
+        // Make room for the missing bytes after the buffered ones
+        if self.buf.remaining_write() < min - len {
+            self.buf.compact();
+            self.buf.reserve(min - len);
+        }
+
+        while self.buf.remaining_read() < min {
+            match self.inner.read_buf(&mut self.buf) {
+                Ok(0) => break,
+                Ok(_) => {}
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(self.buf.remaining_read())
+    }
+
+    /// Returns the capacity of the internal buffer
+    pub fn capacity(&self) -> usize {
+        self.buf.capacity()
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the source. Reading from it directly
+    /// skips over the bytes that have not been buffered yet.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Returns the source. Buffered bytes that have not been read are lost.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R> Buf for ReadBuf<R> {
+    fn remaining(&self) -> usize {
+        self.buf.remaining_read()
+    }
+
+    fn bytes(&self) -> &[u8] {
+        Buf::bytes(&self.buf)
+    }
+
+    fn advance(&mut self, cnt: usize) {
+        Buf::advance(&mut self.buf, cnt)
+    }
+
+    fn read_slice(&mut self, dst: &mut [u8]) {
+        Buf::read_slice(&mut self.buf, dst)
+    }
+}
+
+impl<R: fmt::Debug> fmt::Debug for ReadBuf<R> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("ReadBuf")
+            .field("inner", &self.inner)
+            .field("buffered", &self.buf.remaining_read())
+            .finish()
+    }
+}
+
+/*
+ *
+ * ===== WriteBuf =====
+ *
+ */
+
+/// A `MutBuf` that writes its bytes to an `io::Write` sink.
+///
+/// Bytes are held in a fixed size internal buffer, which is written to the
+/// sink whenever it is full. Slices at least as large as the buffer are
+/// written directly, together with any buffered bytes, using a vectored
+/// write.
+///
+/// `MutBuf` methods cannot fail, so the first I/O error is stored and
+/// returned by the next call to `flush` or `into_inner`. Bytes written in
+/// the meantime are discarded. Dropping a `WriteBuf` flushes it, ignoring
+/// errors.
+///
+/// ```
+/// use bytes::MutBuf;
+/// use bytes::buf::WriteBuf;
+///
+/// let mut buf = WriteBuf::with_capacity(4, vec![]);
+///
+/// buf.write_slice(b"hello");
+/// buf.write_slice(b" world");
+///
+/// assert_eq!(buf.into_inner().unwrap(), b"hello world");
+/// ```
+pub struct WriteBuf<W: io::Write> {
+    // Only `None` once `into_inner` has returned it
+    inner: Option<W>,
+    mem: Box<[u8]>,
+    // Number of buffered bytes
+    len: usize,
+    // The first error returned by `inner` since the last `flush`
+    err: Option<io::Error>,
+}
+
+impl<W: io::Write> WriteBuf<W> {
+    /// Create a new `WriteBuf` with a default capacity of 8 KiB
+    pub fn new(inner: W) -> WriteBuf<W> {
+        WriteBuf::with_capacity(DEFAULT_CAPACITY, inner)
+    }
+
+    /// Create a new `WriteBuf` with the specified capacity
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is 0.
+    pub fn with_capacity(capacity: usize, inner: W) -> WriteBuf<W> {
+        assert!(capacity > 0, "capacity must be greater than 0");
+
+        WriteBuf {
+            inner: Some(inner),
+            mem: alloc::with_capacity(capacity),
+            len: 0,
+            err: None,
+        }
+    }
+
+    /// Returns the capacity of the internal buffer
+    pub fn capacity(&self) -> usize {
+        self.mem.len()
+    }
+
+    /// Returns the bytes that have not been written to the sink yet
+    pub fn buffered(&self) -> &[u8] {
+        &self.mem[..self.len]
+    }
+
+    pub fn get_ref(&self) -> &W {
+        self.inner.as_ref().unwrap()
+    }
+
+    /// Returns a mutable reference to the sink. Writing to it directly
+    /// places bytes before the ones that are still buffered.
+    pub fn get_mut(&mut self) -> &mut W {
+        self.inner.as_mut().unwrap()
+    }
+
+    /// Writes all buffered bytes to the sink and flushes it.
+    ///
+    /// If an error occurred while the buffer was written to automatically,
+    /// that error is returned instead and cleared, along with the bytes
+    /// written since.
+    pub fn flush(&mut self) -> io::Result<()> {
+        if let Some(err) = self.err.take() {
+            self.len = 0;
+            return Err(err);
+        }
+
+        try!(self.write_buffered(&[]));
+        self.get_mut().flush()
+    }
+
+    /// Flushes the `WriteBuf` and returns the sink.
+    pub fn into_inner(mut self) -> io::Result<W> {
+        try!(self.flush());
+        Ok(self.inner.take().unwrap())
+    }
+
+    /// Writes the buffered bytes followed by `src` to the sink. If an error
+    /// is returned, the buffered bytes that were not written are kept.
+    fn write_buffered(&mut self, src: &[u8]) -> io::Result<()> {
+        let mut written = 0;
+        let mut src_pos = 0;
+        let mut ret = Ok(());
+
+        while written < self.len || src_pos < src.len() {
+            let res = {
+                let bufs = [
+                    io::IoSlice::new(&self.mem[written..self.len]),
+                    io::IoSlice::new(&src[src_pos..]),
+                ];
+
+                self.inner.as_mut().unwrap().write_vectored(&bufs)
+            };
+
+            match res {
+                Ok(0) => {
+                    ret = Err(io::Error::new(io::ErrorKind::WriteZero,
+                                             "failed to write the buffered data"));
+                    break;
+                }
+                Ok(n) => {
+                    let from_mem = cmp::min(n, self.len - written);
+                    written += from_mem;
+                    src_pos += n - from_mem;
+                }
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => {
+                    ret = Err(e);
+                    break;
+                }
+            }
+        }
+
+        self.mem.copy_within(written..self.len, 0);
+        self.len -= written;
+        ret
+    }
+
+    /// Make the whole buffer available, recording any error and discarding
+    /// the buffered bytes if they cannot be written
+    fn make_room(&mut self, src: &[u8]) {
+        if self.err.is_none() {
+            if let Err(err) = self.write_buffered(src) {
+                self.err = Some(err);
+            }
+        }
+
+        self.len = 0;
+    }
+}
+
+impl<W: io::Write> MutBuf for WriteBuf<W> {
+    /// A `WriteBuf` can always be written to, as it flushes when full
+    fn remaining(&self) -> usize {
+        MAX_CAPACITY
+    }
+
+    fn is_bounded(&self) -> bool {
+        false
+    }
+
+    /// Writes the buffered bytes to the sink if fewer than `additional`
+    /// bytes are free. `mut_bytes` cannot return more than the capacity of
+    /// the `WriteBuf`.
+    fn reserve(&mut self, additional: usize) {
+        if self.mem.len() - self.len < additional {
+            self.make_room(&[]);
+        }
+    }
+
+    unsafe fn advance(&mut self, cnt: usize) {
+        assert!(cnt <= self.mem.len() - self.len, "buffer overflow");
+        self.len += cnt;
+    }
+
+    fn mut_bytes(&mut self) -> &mut UninitSlice {
+        if self.len == self.mem.len() {
+            self.make_room(&[]);
+        }
+
+        UninitSlice::new(&mut self.mem[self.len..])
+    }
+
+    fn init_bytes_vec<'a>(&'a mut self, dst: &mut [&'a mut [u8]], max: usize) -> usize {
+        if self.len == self.mem.len() {
+            self.make_room(&[]);
+        }
+
+        fill_slices(dst, Some(&mut self.mem[self.len..]), max)
+    }
+
+    fn flush_buf(&mut self) -> io::Result<()> {
+        self.flush()
+    }
+
+    fn write_slice(&mut self, src: &[u8]) {
+        if src.len() >= self.mem.len() {
+            // Too large to be worth copying
+            self.make_room(src);
+            return;
+        }
+
+        if src.len() > self.mem.len() - self.len {
+            self.make_room(&[]);
+        }
+
+        self.mem[self.len..self.len + src.len()].copy_from_slice(src);
+        self.len += src.len();
+    }
+}
+
+impl<W: io::Write> Drop for WriteBuf<W> {
+    fn drop(&mut self) {
+        if self.inner.is_some() && self.err.is_none() {
+            let _ = self.write_buffered(&[]);
+        }
+    }
+}
+
+impl<W: io::Write + fmt::Debug> fmt::Debug for WriteBuf<W> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("WriteBuf")
+            .field("inner", &self.inner)
+            .field("buffered", &self.len)
+            .finish()
+    }
+}
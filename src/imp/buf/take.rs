@@ -1,5 +1,6 @@
-use {Buf, MutBuf};
-use std::{cmp};
+use {Buf, MutBuf, Bytes};
+use buf::{Checkpoint, UninitSlice};
+use std::{cmp, io, mem};
 
 #[derive(Debug)]
 pub struct Take<T> {
@@ -42,7 +43,8 @@ impl<T: Buf> Buf for Take<T> {
     }
 
     fn bytes(&self) -> &[u8] {
-        &self.inner.bytes()[..self.limit]
+        let bytes = self.inner.bytes();
+        &bytes[..cmp::min(bytes.len(), self.limit)]
     }
 
     fn advance(&mut self, cnt: usize) {
@@ -50,6 +52,35 @@ impl<T: Buf> Buf for Take<T> {
         self.limit -= cnt;
         self.inner.advance(cnt);
     }
+
+    fn peek_slice(&self, dst: &mut [u8]) -> usize {
+        let n = cmp::min(dst.len(), self.limit);
+        self.inner.peek_slice(&mut dst[..n])
+    }
+
+    fn bytes_shared(&self) -> Option<Bytes> {
+        self.inner.bytes_shared().map(|bytes| {
+            if bytes.len() > self.limit {
+                bytes.slice_to(self.limit)
+            } else {
+                bytes
+            }
+        })
+    }
+}
+
+/// The limit is restored along with the inner `Buf`.
+impl<T: Checkpoint> Checkpoint for Take<T> {
+    type Mark = (T::Mark, usize);
+
+    fn checkpoint(&self) -> (T::Mark, usize) {
+        (self.inner.checkpoint(), self.limit)
+    }
+
+    fn rewind(&mut self, mark: (T::Mark, usize)) {
+        self.inner.rewind(mark.0);
+        self.limit = mark.1;
+    }
 }
 
 impl<T: MutBuf> MutBuf for Take<T> {
@@ -57,8 +88,45 @@ impl<T: MutBuf> MutBuf for Take<T> {
         cmp::min(self.inner.remaining(), self.limit)
     }
 
-    unsafe fn mut_bytes(&mut self) -> &mut [u8] {
-        &mut self.inner.mut_bytes()[..self.limit]
+    fn reserve(&mut self, additional: usize) {
+        self.inner.reserve(cmp::min(additional, self.limit))
+    }
+
+    fn flush_buf(&mut self) -> io::Result<()> {
+        self.inner.flush_buf()
+    }
+
+    fn mut_bytes(&mut self) -> &mut UninitSlice {
+        let bytes = self.inner.mut_bytes();
+        let n = cmp::min(bytes.len(), self.limit);
+        &mut bytes[..n]
+    }
+
+    fn mut_bytes_vec<'a>(&'a mut self, dst: &mut [&'a mut UninitSlice]) -> usize {
+        let mut rem = self.limit;
+        let n = self.inner.mut_bytes_vec(dst);
+
+        for (i, bytes) in dst[..n].iter_mut().enumerate() {
+            if rem == 0 {
+                return i;
+            }
+
+            let len = cmp::min(bytes.len(), rem);
+            rem -= len;
+
+            let full = mem::replace(bytes, Default::default());
+            *bytes = &mut full[..len];
+        }
+
+        n
+    }
+
+    fn init_bytes_vec<'a>(&'a mut self, dst: &mut [&'a mut [u8]], max: usize) -> usize {
+        self.inner.init_bytes_vec(dst, cmp::min(max, self.limit))
+    }
+
+    fn release_unused(&mut self) {
+        self.inner.release_unused()
     }
 
     unsafe fn advance(&mut self, cnt: usize) {
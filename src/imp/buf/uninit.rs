@@ -0,0 +1,155 @@
+//! A write-only view of memory that may be uninitialized.
+
+use std::{fmt, ops, ptr, slice};
+use std::mem::MaybeUninit;
+
+/// A mutable slice of bytes that may be uninitialized.
+///
+/// `UninitSlice` is returned by `MutBuf::mut_bytes`. Unlike `[u8]`, it only
+/// provides write access, so handing out memory that has not been initialized
+/// yet cannot expose its contents.
+///
+/// ```
+/// use bytes::MutBuf;
+///
+/// let mut buf = Vec::with_capacity(16);
+///
+/// {
+///     let dst = buf.mut_bytes();
+///     dst[..5].copy_from_slice(b"hello");
+/// }
+///
+/// unsafe { buf.advance(5); }
+///
+/// assert_eq!(buf, b"hello");
+/// ```
+#[repr(transparent)]
+pub struct UninitSlice([MaybeUninit<u8>]);
+
+impl UninitSlice {
+    /// Create a `&mut UninitSlice` from a slice of initialized bytes.
+    pub fn new(slice: &mut [u8]) -> &mut UninitSlice {
+        unsafe { UninitSlice::from_raw_parts_mut(slice.as_mut_ptr(), slice.len()) }
+    }
+
+    /// Create a `&mut UninitSlice` from a slice of possibly uninitialized
+    /// bytes.
+    pub fn uninit(slice: &mut [MaybeUninit<u8>]) -> &mut UninitSlice {
+        unsafe { &mut *(slice as *mut [MaybeUninit<u8>] as *mut UninitSlice) }
+    }
+
+    /// Create a `&mut UninitSlice` from a pointer and a length.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that `ptr` references a valid memory region
+    /// owned by the caller, of at least `len` bytes, which is not accessed by
+    /// anything else for the duration of the lifetime `'a`.
+    pub unsafe fn from_raw_parts_mut<'a>(ptr: *mut u8, len: usize) -> &'a mut UninitSlice {
+        let slice = slice::from_raw_parts_mut(ptr as *mut MaybeUninit<u8>, len);
+        UninitSlice::uninit(slice)
+    }
+
+    /// Returns the number of bytes in the slice
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns true if the slice has a length of 0
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Write a single byte at the specified offset.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn write_byte(&mut self, index: usize, byte: u8) {
+        assert!(index < self.len(), "index out of bounds");
+        self.0[index] = MaybeUninit::new(byte);
+    }
+
+    /// Copy all of the bytes from `src` into `self`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` and `src` have different lengths.
+    pub fn copy_from_slice(&mut self, src: &[u8]) {
+        assert_eq!(self.len(), src.len(), "destination and source slices have different lengths");
+
+        unsafe {
+            ptr::copy_nonoverlapping(src.as_ptr(), self.as_mut_ptr(), src.len());
+        }
+    }
+
+    /// Fill the slice with zeros, returning it as a slice of initialized
+    /// bytes.
+    ///
+    /// This is useful for passing the memory to APIs such as `io::Read` that
+    /// require an initialized `&mut [u8]`.
+    pub fn zeroed(&mut self) -> &mut [u8] {
+        unsafe {
+            ptr::write_bytes(self.as_mut_ptr(), 0, self.len());
+            slice::from_raw_parts_mut(self.as_mut_ptr(), self.len())
+        }
+    }
+
+    /// Return a raw pointer to the start of the slice.
+    ///
+    /// The pointer must only be used to write to the memory.
+    pub fn as_mut_ptr(&mut self) -> *mut u8 {
+        self.0.as_mut_ptr() as *mut u8
+    }
+
+    /// Return the slice as a slice of `MaybeUninit<u8>`.
+    ///
+    /// # Safety
+    ///
+    /// The caller must not write uninitialized bytes to memory that was
+    /// previously initialized.
+    pub unsafe fn as_uninit_slice_mut(&mut self) -> &mut [MaybeUninit<u8>] {
+        &mut self.0
+    }
+}
+
+impl fmt::Debug for UninitSlice {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "UninitSlice[len={}]", self.len())
+    }
+}
+
+/// An empty slice, used to initialize the destination of
+/// `MutBuf::mut_bytes_vec`.
+impl<'a> Default for &'a mut UninitSlice {
+    fn default() -> &'a mut UninitSlice {
+        UninitSlice::new(&mut [])
+    }
+}
+
+macro_rules! impl_index {
+    ($($t:ty),*) => {
+        $(
+            impl ops::Index<$t> for UninitSlice {
+                type Output = UninitSlice;
+
+                fn index(&self, index: $t) -> &UninitSlice {
+                    let slice = &self.0[index];
+                    unsafe { &*(slice as *const [MaybeUninit<u8>] as *const UninitSlice) }
+                }
+            }
+
+            impl ops::IndexMut<$t> for UninitSlice {
+                fn index_mut(&mut self, index: $t) -> &mut UninitSlice {
+                    UninitSlice::uninit(&mut self.0[index])
+                }
+            }
+        )*
+    };
+}
+
+impl_index!(
+    ops::Range<usize>,
+    ops::RangeFrom<usize>,
+    ops::RangeTo<usize>,
+    ops::RangeFull);
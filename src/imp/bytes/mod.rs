@@ -3,10 +3,11 @@ pub mod seq;
 pub mod small;
 
 use {Buf, IntoBuf};
+use buf::{Checkpoint, SeekBuf};
 use self::seq::Seq;
 use self::small::Small;
-use self::rope::{Rope, RopeBuf};
-use std::{cmp, fmt, ops};
+use self::rope::{Rope, RopeBuf, Balance};
+use std::{cmp, fmt, iter, ops};
 use std::io::Cursor;
 use std::sync::Arc;
 
@@ -23,7 +24,41 @@ enum Kind {
     Rope(Arc<Rope>),
 }
 
+/// Incrementally assembles a `Bytes` value from a series of pieces.
+///
+/// Pieces pushed onto the builder are not copied; they become the leaves of a
+/// single balanced rope which is returned by `build`. Individual bytes are
+/// collected into a pending chunk first.
+pub struct BytesBuilder {
+    balance: Balance,
+    pending: Vec<u8>,
+}
+
+/// An iterator over sub-slices of a `Bytes`, separated by a byte.
+///
+/// Created by `Bytes::split`.
+pub struct Split {
+    rem: Option<Bytes>,
+    sep: u8,
+}
+
+/// An iterator over at most `n` sub-slices of a `Bytes`, separated by a byte.
+///
+/// Created by `Bytes::splitn`.
+pub struct SplitN {
+    inner: Split,
+    count: usize,
+}
+
+/// An iterator over the lines of a `Bytes`.
+///
+/// Created by `Bytes::lines`.
+pub struct Lines {
+    inner: Split,
+}
+
 pub struct BytesBuf<'a> {
+    src: &'a Bytes,
     kind: BufKind<'a>,
 }
 
@@ -65,7 +100,10 @@ impl Bytes {
             Kind::Rope(ref v) => BufKind::Rope(v.buf()),
         };
 
-        BytesBuf { kind: kind }
+        BytesBuf {
+            src: self,
+            kind: kind,
+        }
     }
 
     pub fn is_empty(&self) -> bool {
@@ -84,6 +122,51 @@ impl Bytes {
         Rope::concat(self.clone(), other.clone())
     }
 
+    /// Collect the remaining bytes of `buf` into a `Bytes` value.
+    ///
+    /// If `buf` is backed by reference counted memory, as is the case for
+    /// `BytesBuf` or `BlockBufCursor`, the result is assembled from that memory
+    /// without copying. Otherwise, the bytes are copied once into a new
+    /// allocation of exactly the right size.
+    pub fn from_buf<B: Buf>(mut buf: B) -> Bytes {
+        if buf.bytes_shared().is_none() {
+            let mut vec = Vec::with_capacity(buf.remaining());
+
+            while buf.has_remaining() {
+                let n = {
+                    let src = buf.bytes();
+                    vec.extend_from_slice(src);
+                    src.len()
+                };
+
+                buf.advance(n);
+            }
+
+            return Bytes::from(vec);
+        }
+
+        let mut builder = BytesBuilder::new();
+
+        while buf.has_remaining() {
+            let chunk = buf.bytes_shared()
+                .unwrap_or_else(|| Bytes::from_slice(buf.bytes()));
+
+            let n = chunk.len();
+            builder.push(chunk);
+            buf.advance(n);
+        }
+
+        builder.build()
+    }
+
+    /// Concatenate all of the given values into a single `Bytes`.
+    ///
+    /// The result is assembled in a single pass, unlike repeated calls to
+    /// `concat` which may rebalance the rope on each call.
+    pub fn concat_all(pieces: &[Bytes]) -> Bytes {
+        pieces.iter().cloned().collect()
+    }
+
     /// Returns a new ByteStr value containing the byte range between `begin`
     /// (inclusive) and `end` (exclusive)
     pub fn slice(&self, begin: usize, end: usize) -> Bytes {
@@ -110,6 +193,192 @@ impl Bytes {
         self.slice(0, end)
     }
 
+    /// Returns true if the bytes start with `prefix`
+    pub fn starts_with(&self, prefix: &[u8]) -> bool {
+        if prefix.len() > self.len() {
+            return false;
+        }
+
+        self.eq_prefix(prefix, |a, b| a == b)
+    }
+
+    /// Returns true if the bytes end with `suffix`
+    pub fn ends_with(&self, suffix: &[u8]) -> bool {
+        if suffix.len() > self.len() {
+            return false;
+        }
+
+        self.slice_from(self.len() - suffix.len()).starts_with(suffix)
+    }
+
+    /// Returns true if `needle` is a sub-slice of the bytes
+    pub fn contains(&self, needle: &[u8]) -> bool {
+        if needle.is_empty() {
+            return true;
+        }
+
+        if needle.len() > self.len() {
+            return false;
+        }
+
+        // Matches may straddle chunk boundaries, so keep track of the last
+        // `needle.len() - 1` bytes of the previous chunks.
+        let keep = needle.len() - 1;
+        let mut tail: Vec<u8> = Vec::with_capacity(2 * keep);
+        let mut ret = false;
+
+        self.each_chunk(|chunk| {
+            if !tail.is_empty() {
+                let n = cmp::min(keep, chunk.len());
+                let mut joined = tail.clone();
+                joined.extend_from_slice(&chunk[..n]);
+
+                if contains_slice(&joined, needle) {
+                    ret = true;
+                    return false;
+                }
+            }
+
+            if contains_slice(chunk, needle) {
+                ret = true;
+                return false;
+            }
+
+            if chunk.len() >= keep {
+                tail.clear();
+                tail.extend_from_slice(&chunk[chunk.len() - keep..]);
+            } else {
+                tail.extend_from_slice(chunk);
+
+                if tail.len() > keep {
+                    let excess = tail.len() - keep;
+                    tail.drain(..excess);
+                }
+            }
+
+            true
+        });
+
+        ret
+    }
+
+    /// Returns a new `Bytes` value with the leading and trailing ASCII
+    /// whitespace removed
+    pub fn trim_ascii(&self) -> Bytes {
+        let mut begin = 0;
+        let mut end = self.len();
+
+        while begin < end && is_ascii_whitespace(self[begin]) {
+            begin += 1;
+        }
+
+        while end > begin && is_ascii_whitespace(self[end - 1]) {
+            end -= 1;
+        }
+
+        self.slice(begin, end)
+    }
+
+    /// Returns true if the bytes are equal to `other`, ignoring ASCII case
+    pub fn eq_ignore_ascii_case(&self, other: &[u8]) -> bool {
+        if self.len() != other.len() {
+            return false;
+        }
+
+        self.eq_prefix(other, |a, b| a.eq_ignore_ascii_case(b))
+    }
+
+    /// Returns an iterator over the sub-slices separated by `sep`.
+    ///
+    /// The sub-slices share memory with `self`; no bytes are copied.
+    pub fn split(&self, sep: u8) -> Split {
+        Split {
+            rem: Some(self.clone()),
+            sep: sep,
+        }
+    }
+
+    /// Returns an iterator over the sub-slices separated by `sep`, yielding
+    /// at most `n` items. The last item contains the remainder of the bytes.
+    pub fn splitn(&self, n: usize, sep: u8) -> SplitN {
+        SplitN {
+            inner: self.split(sep),
+            count: n,
+        }
+    }
+
+    /// Returns an iterator over the lines of the bytes.
+    ///
+    /// Lines are terminated by either `\n` or `\r\n`, which are not included
+    /// in the yielded values. The final line ending is optional.
+    pub fn lines(&self) -> Lines {
+        Lines { inner: self.split(b'\n') }
+    }
+
+    /// Returns the index of the first occurrence of `byte`
+    fn find_byte(&self, byte: u8) -> Option<usize> {
+        let mut pos = 0;
+        let mut ret = None;
+
+        self.each_chunk(|chunk| {
+            match chunk.iter().position(|&b| b == byte) {
+                Some(i) => {
+                    ret = Some(pos + i);
+                    false
+                }
+                None => {
+                    pos += chunk.len();
+                    true
+                }
+            }
+        });
+
+        ret
+    }
+
+    /// Compare the first `other.len()` bytes with `other` using `eq`, which
+    /// is called with pairs of slices of equal length.
+    fn eq_prefix<F>(&self, other: &[u8], mut eq: F) -> bool
+        where F: FnMut(&[u8], &[u8]) -> bool,
+    {
+        let mut rem = other;
+        let mut ret = true;
+
+        self.each_chunk(|chunk| {
+            let n = cmp::min(chunk.len(), rem.len());
+
+            if !eq(&chunk[..n], &rem[..n]) {
+                ret = false;
+                return false;
+            }
+
+            rem = &rem[n..];
+            !rem.is_empty()
+        });
+
+        ret
+    }
+
+    /// Calls `f` with each contiguous chunk of bytes, in order, until `f`
+    /// returns false.
+    fn each_chunk<F: FnMut(&[u8]) -> bool>(&self, mut f: F) {
+        let mut buf = self.buf();
+
+        while buf.has_remaining() {
+            let n = {
+                let chunk = buf.bytes();
+
+                if !f(chunk) {
+                    return;
+                }
+
+                chunk.len()
+            };
+
+            buf.advance(n);
+        }
+    }
+
     /// Returns the Rope depth
     fn depth(&self) -> u16 {
         match self.kind {
@@ -141,6 +410,21 @@ impl From<Vec<u8>> for Bytes {
     }
 }
 
+impl iter::FromIterator<u8> for Bytes {
+    fn from_iter<T: IntoIterator<Item = u8>>(iter: T) -> Bytes {
+        let vec: Vec<u8> = iter.into_iter().collect();
+        Bytes::from(vec)
+    }
+}
+
+impl iter::FromIterator<Bytes> for Bytes {
+    fn from_iter<T: IntoIterator<Item = Bytes>>(iter: T) -> Bytes {
+        let mut builder = BytesBuilder::new();
+        builder.extend(iter);
+        builder.build()
+    }
+}
+
 impl ops::Index<usize> for Bytes {
     type Output = u8;
 
@@ -196,6 +480,149 @@ impl<'a> IntoBuf for &'a Bytes {
     }
 }
 
+/*
+ *
+ * ===== BytesBuilder =====
+ *
+ */
+
+impl BytesBuilder {
+    /// Return a new, empty `BytesBuilder`
+    pub fn new() -> BytesBuilder {
+        BytesBuilder {
+            balance: Balance::new(),
+            pending: vec![],
+        }
+    }
+
+    /// Returns the number of bytes pushed onto the builder so far
+    pub fn len(&self) -> usize {
+        self.balance.len() + self.pending.len()
+    }
+
+    /// Returns true if no bytes have been pushed onto the builder
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Append `bytes` to the value being built
+    pub fn push(&mut self, bytes: Bytes) {
+        self.flush_pending();
+        self.balance.push(bytes);
+    }
+
+    /// Consume the builder, returning the assembled `Bytes`
+    pub fn build(mut self) -> Bytes {
+        self.flush_pending();
+        self.balance.finish()
+    }
+
+    fn flush_pending(&mut self) {
+        if !self.pending.is_empty() {
+            let pending = ::std::mem::replace(&mut self.pending, vec![]);
+            self.balance.push(Bytes::from(pending));
+        }
+    }
+}
+
+impl Default for BytesBuilder {
+    fn default() -> BytesBuilder {
+        BytesBuilder::new()
+    }
+}
+
+impl Extend<Bytes> for BytesBuilder {
+    fn extend<T: IntoIterator<Item = Bytes>>(&mut self, iter: T) {
+        for bytes in iter {
+            self.push(bytes);
+        }
+    }
+}
+
+impl<'a> Extend<&'a Bytes> for BytesBuilder {
+    fn extend<T: IntoIterator<Item = &'a Bytes>>(&mut self, iter: T) {
+        self.extend(iter.into_iter().cloned())
+    }
+}
+
+impl Extend<u8> for BytesBuilder {
+    fn extend<T: IntoIterator<Item = u8>>(&mut self, iter: T) {
+        self.pending.extend(iter)
+    }
+}
+
+impl<'a> Extend<&'a u8> for BytesBuilder {
+    fn extend<T: IntoIterator<Item = &'a u8>>(&mut self, iter: T) {
+        self.pending.extend(iter)
+    }
+}
+
+/*
+ *
+ * ===== Split iterators =====
+ *
+ */
+
+impl Iterator for Split {
+    type Item = Bytes;
+
+    fn next(&mut self) -> Option<Bytes> {
+        let rem = match self.rem.take() {
+            Some(rem) => rem,
+            None => return None,
+        };
+
+        match rem.find_byte(self.sep) {
+            Some(i) => {
+                self.rem = Some(rem.slice_from(i + 1));
+                Some(rem.slice_to(i))
+            }
+            None => Some(rem),
+        }
+    }
+}
+
+impl Iterator for SplitN {
+    type Item = Bytes;
+
+    fn next(&mut self) -> Option<Bytes> {
+        match self.count {
+            0 => None,
+            1 => {
+                self.count = 0;
+                self.inner.rem.take()
+            }
+            _ => {
+                self.count -= 1;
+                self.inner.next()
+            }
+        }
+    }
+}
+
+impl Iterator for Lines {
+    type Item = Bytes;
+
+    fn next(&mut self) -> Option<Bytes> {
+        let line = match self.inner.next() {
+            Some(line) => line,
+            None => return None,
+        };
+
+        // The empty slice following the final line ending is not a line
+        if line.is_empty() && self.inner.rem.is_none() {
+            return None;
+        }
+
+        // Only strip a `\r` that precedes the `\n` ending the line
+        if self.inner.rem.is_some() && line.ends_with(b"\r") {
+            Some(line.slice_to(line.len() - 1))
+        } else {
+            Some(line)
+        }
+    }
+}
+
 /*
  *
  * ===== BytesBuf =====
@@ -223,8 +650,49 @@ impl<'a> Buf for BytesBuf<'a> {
             BufKind::Rope(ref mut v) => v.advance(cnt),
         }
     }
+
+    fn peek_slice(&self, dst: &mut [u8]) -> usize {
+        match self.kind {
+            BufKind::Cursor(ref v) => v.peek_slice(dst),
+            BufKind::Rope(ref v) => v.peek_slice(dst),
+        }
+    }
+
+    fn bytes_shared(&self) -> Option<Bytes> {
+        match self.kind {
+            BufKind::Cursor(ref v) => Some(self.src.slice_from(v.position() as usize)),
+            BufKind::Rope(ref v) => v.bytes_shared(),
+        }
+    }
+}
+
+impl<'a> SeekBuf for BytesBuf<'a> {
+    fn position(&self) -> usize {
+        match self.kind {
+            BufKind::Cursor(ref v) => v.position() as usize,
+            BufKind::Rope(ref v) => v.position(),
+        }
+    }
+
+    fn set_position(&mut self, pos: usize) {
+        match self.kind {
+            BufKind::Cursor(ref mut v) => SeekBuf::set_position(v, pos),
+            BufKind::Rope(ref mut v) => v.set_position(pos),
+        }
+    }
 }
 
+impl<'a> Checkpoint for BytesBuf<'a> {
+    type Mark = usize;
+
+    fn checkpoint(&self) -> usize {
+        self.position()
+    }
+
+    fn rewind(&mut self, mark: usize) {
+        self.set_position(mark)
+    }
+}
 
 /*
  *
@@ -263,6 +731,17 @@ impl fmt::Debug for Bytes {
     }
 }
 
+fn is_ascii_whitespace(byte: u8) -> bool {
+    match byte {
+        b' ' | b'\t' | b'\n' | b'\r' | 0x0C => true,
+        _ => false,
+    }
+}
+
+fn contains_slice(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack.windows(needle.len()).any(|w| w == needle)
+}
+
 fn is_ascii(byte: u8) -> bool {
     match byte {
         10 | 13 | 32...126 => true,
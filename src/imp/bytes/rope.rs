@@ -1,8 +1,8 @@
-use {Buf, MutBuf, Bytes};
+use {Buf, MutBuf, MutBufExt, Bytes};
 use super::seq::Seq;
 use super::small::{Small};
-use buf::{Source, AppendBuf};
-use std::{cmp, ops};
+use buf::{Source, AppendBuf, SeekBuf};
+use std::{cmp, mem, ops, usize};
 use std::io::Cursor;
 use std::sync::Arc;
 
@@ -10,20 +10,61 @@ use std::sync::Arc;
 // protobuf lib.
 
 const CONCAT_BY_COPY_LEN: usize = 128;
+
+// Used to decide when to rebalance the tree. The table covers the Fibonacci
+// numbers representable by `usize`, terminated by `usize::MAX`.
+#[cfg(target_pointer_width = "64")]
+const MAX_DEPTH: usize = 93;
+
+#[cfg(target_pointer_width = "64")]
+static MIN_LENGTH_BY_DEPTH: [usize; MAX_DEPTH] = [
+                             1,                          2,                          3,
+                             5,                          8,                         13,
+                            21,                         34,                         55,
+                            89,                        144,                        233,
+                           377,                        610,                        987,
+                         1_597,                      2_584,                      4_181,
+                         6_765,                     10_946,                     17_711,
+                        28_657,                     46_368,                     75_025,
+                       121_393,                    196_418,                    317_811,
+                       514_229,                    832_040,                  1_346_269,
+                     2_178_309,                  3_524_578,                  5_702_887,
+                     9_227_465,                 14_930_352,                 24_157_817,
+                    39_088_169,                 63_245_986,                102_334_155,
+                   165_580_141,                267_914_296,                433_494_437,
+                   701_408_733,              1_134_903_170,              1_836_311_903,
+                 2_971_215_073,              4_807_526_976,              7_778_742_049,
+                12_586_269_025,             20_365_011_074,             32_951_280_099,
+                53_316_291_173,             86_267_571_272,            139_583_862_445,
+               225_851_433_717,            365_435_296_162,            591_286_729_879,
+               956_722_026_041,          1_548_008_755_920,          2_504_730_781_961,
+             4_052_739_537_881,          6_557_470_319_842,         10_610_209_857_723,
+            17_167_680_177_565,         27_777_890_035_288,         44_945_570_212_853,
+            72_723_460_248_141,        117_669_030_460_994,        190_392_490_709_135,
+           308_061_521_170_129,        498_454_011_879_264,        806_515_533_049_393,
+         1_304_969_544_928_657,      2_111_485_077_978_050,      3_416_454_622_906_707,
+         5_527_939_700_884_757,      8_944_394_323_791_464,     14_472_334_024_676_221,
+        23_416_728_348_467_685,     37_889_062_373_143_906,     61_305_790_721_611_591,
+        99_194_853_094_755_497,    160_500_643_816_367_088,    259_695_496_911_122_585,
+       420_196_140_727_489_673,    679_891_637_638_612_258,  1_100_087_778_366_101_931,
+     1_779_979_416_004_714_189,  2_880_067_194_370_816_120,  4_660_046_610_375_530_309,
+     7_540_113_804_746_346_429, 12_200_160_415_121_876_738,                 usize::MAX];
+
+#[cfg(target_pointer_width = "32")]
 const MAX_DEPTH: usize = 47;
 
-// Used to decide when to rebalance the tree.
+#[cfg(target_pointer_width = "32")]
 static MIN_LENGTH_BY_DEPTH: [usize; MAX_DEPTH] = [
-              1,              2,              3,              5,              8,
-             13,             21,             34,             55,             89,
-            144,            233,            377,            610,            987,
-          1_597,          2_584,          4_181,          6_765,         10_946,
-         17_711,         28_657,         46_368,         75_025,        121_393,
-        196_418,        317_811,        514_229,        832_040,      1_346_269,
-      2_178_309,      3_524_578,      5_702_887,      9_227_465,     14_930_352,
-     24_157_817,     39_088_169,     63_245_986,    102_334_155,    165_580_141,
-    267_914_296,    433_494_437,    701_408_733,  1_134_903_170,  1_836_311_903,
-  2_971_215_073,  4_294_967_295];
+                1,             2,             3,             5,             8,
+               13,            21,            34,            55,            89,
+              144,           233,           377,           610,           987,
+            1_597,         2_584,         4_181,         6_765,        10_946,
+           17_711,        28_657,        46_368,        75_025,       121_393,
+          196_418,       317_811,       514_229,       832_040,     1_346_269,
+        2_178_309,     3_524_578,     5_702_887,     9_227_465,    14_930_352,
+       24_157_817,    39_088_169,    63_245_986,   102_334_155,   165_580_141,
+      267_914_296,   433_494_437,   701_408_733, 1_134_903_170, 1_836_311_903,
+    2_971_215_073,    usize::MAX];
 
 /// An immutable sequence of bytes formed by concatenation of other `ByteStr`
 /// values, without copying the data in the pieces. The concatenation is
@@ -49,12 +90,18 @@ pub struct Rope {
 }
 
 pub struct RopeBuf<'a> {
+    // The rope being iterated, used to seek
+    root: &'a Rope,
+
     // Number of bytes left to iterate
     rem: usize,
 
     // Iterates all the leaf nodes in order
     nodes: NodeIter<'a>,
 
+    // Current leaf node
+    leaf: Option<&'a Node>,
+
     // Current leaf node buffer
     leaf_buf: Option<Cursor<&'a [u8]>>,
 }
@@ -68,13 +115,14 @@ enum Node {
 }
 
 // TODO: store stack inline if possible
+#[derive(Clone)]
 struct NodeIter<'a> {
     stack: Vec<&'a Rope>,
     next: Option<&'a Node>,
 }
 
 /// Balance operation state
-struct Balance {
+pub struct Balance {
     stack: Vec<Partial>,
 }
 
@@ -92,7 +140,8 @@ impl Rope {
         debug_assert!(!left.is_empty() || right.is_empty());
 
         // If left is 0 then right must be zero
-        let len = left.len() + right.len();
+        let len = left.len().checked_add(right.len())
+            .expect("rope length overflow");
         let depth = cmp::max(left.depth(), right.depth()) + 1;
 
         Rope {
@@ -107,12 +156,14 @@ impl Rope {
         let mut nodes = NodeIter::new(self);
 
         // Get the next leaf node buffer
-        let leaf_buf = nodes.next()
-            .map(|node| node.leaf_buf());
+        let leaf = nodes.next();
+        let leaf_buf = leaf.map(|node| node.leaf_buf());
 
         RopeBuf {
+            root: self,
             rem: self.len(),
             nodes: nodes,
+            leaf: leaf,
             leaf_buf: leaf_buf,
         }
     }
@@ -175,7 +226,7 @@ impl Rope {
         // rebalance ;^)
         let depth = cmp::max(left.depth(), right.depth()) + 1;
 
-        if len >= MIN_LENGTH_BY_DEPTH[depth as usize] {
+        if len >= min_length_by_depth(depth) {
             // No need to rebalance
             return Rope::new(left, right).into_bytes();
         }
@@ -195,7 +246,7 @@ impl Rope {
         self.len() == 0
     }
 
-    pub fn slice(&self, begin: usize, end: usize) -> Bytes {
+    pub fn slice(&self, mut begin: usize, mut end: usize) -> Bytes {
         // Assert args
         assert!(begin <= end && end <= self.len(), "invalid range");
 
@@ -213,25 +264,48 @@ impl Rope {
 
         // == Proper substring ==
 
-        let left_len = self.left.len();
+        // Descend the tree while the range is contained by a single branch.
+        // This is done with a loop rather than recursion, as ropes that have
+        // not been rebalanced may be very deep.
+        let mut rope = self;
 
-        if end <= left_len {
-            // Slice on the left
-            return self.left.slice(begin, end);
-        }
+        loop {
+            let left_len = rope.left.len();
+
+            let node = if end <= left_len {
+                // Slice on the left
+                &rope.left
+            } else if begin >= left_len {
+                // Slice on the right
+                begin -= left_len;
+                end -= left_len;
+                &rope.right
+            } else {
+                break;
+            };
 
-        if begin >= left_len {
-            // Slice on the right
-            return self.right.slice(begin - left_len, end - left_len);
+            match *node {
+                Node::Rope(ref v) if len == v.len() => return node.clone().into(),
+                Node::Rope(ref v) => rope = v,
+                _ => return node.slice(begin, end),
+            }
         }
 
         // Split slice
-        let left_slice = self.left.slice(begin, self.left.len());
-        let right_slice = self.right.slice(0, end - left_len);
+        let left_len = rope.left.len();
+        let left_slice = rope.left.slice_from(begin);
+        let right_slice = rope.right.slice_to(end - left_len);
 
         Rope::new(left_slice, right_slice).into_bytes()
     }
 
+    fn into_children(mut self) -> (Node, Node) {
+        let left = mem::replace(&mut self.left, Node::Empty);
+        let right = mem::replace(&mut self.right, Node::Empty);
+
+        (left, right)
+    }
+
     fn into_bytes(self) -> Bytes {
         use super::Kind;
         Bytes { kind: Kind::Rope(Arc::new(self)) }
@@ -268,6 +342,84 @@ impl Node {
         }
     }
 
+    /// Returns the bytes from `begin` to the end of the node.
+    fn slice_from(&self, mut begin: usize) -> Bytes {
+        let mut node = self;
+        let mut rights = vec![];
+        let mut ret;
+
+        loop {
+            if begin == 0 {
+                ret = node.clone().into();
+                break;
+            }
+
+            match *node {
+                Node::Rope(ref v) => {
+                    let left_len = v.left.len();
+
+                    if begin >= left_len {
+                        begin -= left_len;
+                        node = &v.right;
+                    } else {
+                        rights.push(&v.right);
+                        node = &v.left;
+                    }
+                }
+                _ => {
+                    ret = node.slice(begin, node.len());
+                    break;
+                }
+            }
+        }
+
+        // Reattach the right branches, innermost first
+        while let Some(right) = rights.pop() {
+            ret = Rope::new(ret, right.clone()).into_bytes();
+        }
+
+        ret
+    }
+
+    /// Returns the bytes from the start of the node up to `end`.
+    fn slice_to(&self, mut end: usize) -> Bytes {
+        let mut node = self;
+        let mut lefts = vec![];
+        let mut ret;
+
+        loop {
+            if end == node.len() {
+                ret = node.clone().into();
+                break;
+            }
+
+            match *node {
+                Node::Rope(ref v) => {
+                    let left_len = v.left.len();
+
+                    if end <= left_len {
+                        node = &v.left;
+                    } else {
+                        lefts.push(&v.left);
+                        end -= left_len;
+                        node = &v.right;
+                    }
+                }
+                _ => {
+                    ret = node.slice(0, end);
+                    break;
+                }
+            }
+        }
+
+        // Reattach the left branches, innermost first
+        while let Some(left) = lefts.pop() {
+            ret = Rope::new(left.clone(), ret).into_bytes();
+        }
+
+        ret
+    }
+
     fn leaf_buf(&self) -> Cursor<&[u8]> {
         match *self {
             Node::Seq(ref v) => v.buf(),
@@ -285,7 +437,7 @@ impl Node {
 }
 
 impl<'a> Source for &'a Node {
-    fn source<B: MutBuf>(self, buf: &mut B) {
+    fn source<B: MutBuf + ?Sized>(self, buf: &mut B) {
         match *self {
             Node::Seq(ref b) => b.as_slice().source(buf),
             Node::Small(ref b) => b.as_ref().source(buf),
@@ -307,6 +459,21 @@ impl From<Bytes> for Node {
     }
 }
 
+impl From<Node> for Bytes {
+    fn from(src: Node) -> Bytes {
+        use super::Kind;
+
+        let kind = match src {
+            Node::Seq(b) => Kind::Seq(b),
+            Node::Small(b) => Kind::Small(b),
+            Node::Rope(b) => Kind::Rope(b),
+            Node::Empty => return Bytes::empty(),
+        };
+
+        Bytes { kind: kind }
+    }
+}
+
 impl From<Rope> for Node {
     fn from(src: Rope) -> Node {
         Node::Rope(Arc::new(src))
@@ -316,15 +483,54 @@ impl From<Rope> for Node {
 impl ops::Index<usize> for Rope {
     type Output = u8;
 
-    fn index(&self, index: usize) -> &u8 {
+    fn index(&self, mut index: usize) -> &u8 {
         assert!(index < self.len());
 
-        let left_len = self.left.len();
+        let mut rope = self;
+
+        loop {
+            let left_len = rope.left.len();
+
+            let node = if index < left_len {
+                &rope.left
+            } else {
+                index -= left_len;
+                &rope.right
+            };
+
+            match *node {
+                Node::Rope(ref v) => rope = v,
+                _ => return node.index(index),
+            }
+        }
+    }
+}
+
+impl Drop for Rope {
+    fn drop(&mut self) {
+        // Fast path, the children do not own any other rope nodes
+        if self.left.as_rope().is_none() && self.right.as_rope().is_none() {
+            return;
+        }
 
-        if index < left_len {
-            self.left.index(index)
-        } else {
-            self.right.index(index - left_len)
+        // Tear down the tree using an explicit stack. Letting the compiler
+        // generated drop glue handle it would recurse once per level, which
+        // can overflow the stack for deep ropes.
+        let mut stack = vec![
+            mem::replace(&mut self.left, Node::Empty),
+            mem::replace(&mut self.right, Node::Empty),
+        ];
+
+        while let Some(node) = stack.pop() {
+            if let Node::Rope(v) = node {
+                // Only descend into nodes that are not referenced elsewhere
+                if let Ok(rope) = Arc::try_unwrap(v) {
+                    let (left, right) = rope.into_children();
+
+                    stack.push(left);
+                    stack.push(right);
+                }
+            }
         }
     }
 }
@@ -359,6 +565,15 @@ fn concat_bytes<S1, S2>(left: S1, right: S2, len: usize) -> Bytes
     return buf.into();
 }
 
+/// Returns the minimum length of a balanced rope of the given depth.
+///
+/// Ropes deeper than the table are never balanced.
+fn min_length_by_depth(depth: u16) -> usize {
+    MIN_LENGTH_BY_DEPTH.get(depth as usize)
+        .cloned()
+        .unwrap_or(usize::MAX)
+}
+
 fn depth_for_len(len: usize) -> u16 {
     match MIN_LENGTH_BY_DEPTH.binary_search(&len) {
         Ok(idx) => idx as u16,
@@ -426,6 +641,43 @@ impl<'a> NodeIter<'a> {
     }
 }
 
+impl<'a> NodeIter<'a> {
+    /// Returns an iterator positioned at the leaf containing byte `pos`,
+    /// along with that leaf and the offset of `pos` within it. The tree is
+    /// descended once, so this is O(depth).
+    fn seek(root: &'a Rope, mut pos: usize) -> (NodeIter<'a>, &'a Node, usize) {
+        debug_assert!(pos < root.len());
+
+        let mut iter = NodeIter {
+            stack: vec![],
+            next: None,
+        };
+
+        let mut rope = root;
+
+        let leaf = loop {
+            let left_len = rope.left.len();
+
+            let node = if pos < left_len {
+                // The right branch is visited once the left one is done
+                iter.stack.push(rope);
+                &rope.left
+            } else {
+                pos -= left_len;
+                &rope.right
+            };
+
+            match node.as_rope() {
+                Some(child) => rope = child,
+                None => break node,
+            }
+        };
+
+        iter.next = iter.next_non_empty_leaf();
+        (iter, leaf, pos)
+    }
+}
+
 impl<'a> Iterator for NodeIter<'a> {
     type Item = &'a Node;
 
@@ -471,9 +723,69 @@ impl<'a> Buf for RopeBuf<'a> {
                 cnt -= curr.remaining();
             }
 
-            self.leaf_buf = self.nodes.next()
-                .map(|node| node.leaf_buf());
+            self.leaf = self.nodes.next();
+            self.leaf_buf = self.leaf.map(|node| node.leaf_buf());
+        }
+    }
+
+    fn peek_slice(&self, dst: &mut [u8]) -> usize {
+        let mut n = cmp::min(dst.len(), self.rem);
+        let mut off = 0;
+        let rest = self.nodes.clone().map(|node| node.leaf_buf().into_inner());
+
+        for src in Some(self.bytes()).into_iter().chain(rest) {
+            let len = cmp::min(src.len(), n);
+
+            dst[off..off + len].copy_from_slice(&src[..len]);
+            off += len;
+            n -= len;
+
+            if n == 0 {
+                break;
+            }
+        }
+
+        off
+    }
+
+    fn bytes_shared(&self) -> Option<Bytes> {
+        match (self.leaf, self.leaf_buf.as_ref()) {
+            (Some(leaf), Some(buf)) => {
+                Some(leaf.slice(buf.position() as usize, leaf.len()))
+            }
+            _ => Some(Bytes::empty()),
+        }
+    }
+}
+
+impl<'a> SeekBuf for RopeBuf<'a> {
+    fn position(&self) -> usize {
+        self.root.len() - self.rem
+    }
+
+    /// Seeking descends the tree from the root, which is O(log n) for a
+    /// balanced rope.
+    fn set_position(&mut self, pos: usize) {
+        let len = self.root.len();
+
+        assert!(pos <= len, "position out of bounds");
+
+        self.rem = len - pos;
+
+        if pos == len {
+            self.nodes = NodeIter { stack: vec![], next: None };
+            self.leaf = None;
+            self.leaf_buf = None;
+            return;
         }
+
+        let (nodes, leaf, offset) = NodeIter::seek(self.root, pos);
+        let mut leaf_buf = leaf.leaf_buf();
+        leaf_buf.set_position(offset as u64);
+
+        self.nodes = nodes;
+        self.leaf = Some(leaf);
+        self.leaf_buf = Some(leaf_buf);
     }
 }
 
@@ -484,40 +796,67 @@ impl<'a> Buf for RopeBuf<'a> {
  */
 
 impl Balance {
-    fn new() -> Balance {
+    pub fn new() -> Balance {
         Balance { stack: vec![] }
     }
 
     fn balance(&mut self, left: Bytes, right: Bytes) -> Bytes {
-        self.do_balance(Partial::Bytes(left));
-        self.do_balance(Partial::Bytes(right));
+        self.push(left);
+        self.push(right);
+        self.finish()
+    }
 
-        let mut partial = self.stack.pop()
-            .expect("expected a value");
+    /// Append `bytes` to the right of the rope being assembled.
+    pub fn push(&mut self, bytes: Bytes) {
+        self.do_balance(Partial::Bytes(bytes));
+    }
 
-        while !partial.is_empty() {
-            let new_left = self.stack.pop()
-                .expect("expected a value");
+    /// Returns the total number of bytes pushed so far.
+    pub fn len(&self) -> usize {
+        self.stack.iter().map(|p| p.len()).fold(0, |a, b| a + b)
+    }
+
+    /// Concatenate all the pieces on the stack into a single `Bytes`, leaving
+    /// the balance state empty.
+    pub fn finish(&mut self) -> Bytes {
+        let mut partial = match self.stack.pop() {
+            Some(partial) => partial,
+            None => return Bytes::empty(),
+        };
 
+        while let Some(new_left) = self.stack.pop() {
             partial = Partial::Bytes(Rope::new(new_left, partial).into_bytes());
         }
 
-        partial.unwrap_bytes()
+        partial.into_bytes()
     }
 
     fn do_balance(&mut self, root: Partial) {
-      // BAP95: Insert balanced subtrees whole. This means the result might not
-      // be balanced, leading to repeated rebalancings on concatenate. However,
-      // these rebalancings are shallow due to ignoring balanced subtrees, and
-      // relatively few calls to insert() result.
-      if root.is_balanced() {
-          self.insert(root);
-      } else {
-          let rope = root.unwrap_rope();
+        // Unbalanced subtrees are split using an explicit stack of pending
+        // branches, as they may be arbitrarily deep.
+        let mut pending = vec![root];
 
-          self.do_balance(Partial::Node(rope.left));
-          self.do_balance(Partial::Node(rope.right));
-      }
+        while let Some(root) = pending.pop() {
+            // Empty pieces do not contribute to the rope
+            if root.is_empty() {
+                continue;
+            }
+
+            // BAP95: Insert balanced subtrees whole. This means the result
+            // might not be balanced, leading to repeated rebalancings on
+            // concatenate. However, these rebalancings are shallow due to
+            // ignoring balanced subtrees, and relatively few calls to insert()
+            // result.
+            if root.is_balanced() {
+                self.insert(root);
+            } else {
+                let (left, right) = root.unwrap_rope().into_children();
+
+                // The left branch is inserted first
+                pending.push(Partial::Node(right));
+                pending.push(Partial::Node(left));
+            }
+        }
     }
 
     // Push a string on the balance stack (BAP95).  BAP95 uses an array and
@@ -532,21 +871,22 @@ impl Balance {
     // concatenation.
     fn insert(&mut self, bytes: Partial) {
         let depth_bin = depth_for_len(bytes.len());
-        let bin_end = MIN_LENGTH_BY_DEPTH[depth_bin as usize + 1];
+        let bin_end = min_length_by_depth(depth_bin + 1);
 
         // BAP95: Concatenate all trees occupying bins representing the length
         // of our new piece or of shorter pieces, to the extent that is
         // possible.  The goal is to clear the bin which our piece belongs in,
         // but that may not be entirely possible if there aren't enough longer
         // bins occupied.
-        if let Some(len) = self.peek().map(|r| r.len()) {
-            if len >= bin_end {
+        match self.peek().map(|r| r.len()) {
+            Some(len) if len < bin_end => {}
+            _ => {
                 self.stack.push(bytes);
                 return;
             }
         }
 
-        let bin_start = MIN_LENGTH_BY_DEPTH[depth_bin as usize];
+        let bin_start = min_length_by_depth(depth_bin);
 
         // Concatenate the subtrees of shorter length
         let mut new_tree = self.stack.pop()
@@ -568,7 +908,7 @@ impl Balance {
         // Continue concatenating until we land in an empty bin
         while let Some(len) = self.peek().map(|r| r.len()) {
             let depth_bin = depth_for_len(new_tree.len());
-            let bin_end = MIN_LENGTH_BY_DEPTH[depth_bin as usize + 1];
+            let bin_end = min_length_by_depth(depth_bin + 1);
 
             if len < bin_end {
                 let left = self.stack.pop()
@@ -608,13 +948,13 @@ impl Partial {
     }
 
     fn is_balanced(&self) -> bool {
-        self.len() >= MIN_LENGTH_BY_DEPTH[self.depth() as usize]
+        self.len() >= min_length_by_depth(self.depth())
     }
 
-    fn unwrap_bytes(self) -> Bytes {
+    fn into_bytes(self) -> Bytes {
         match self {
             Partial::Bytes(v) => v,
-            _ => panic!("unexpected state calling `Partial::unwrap_bytes()`. Expected `Bytes`, got `Node`"),
+            Partial::Node(v) => v.into(),
         }
     }
 
@@ -640,3 +980,52 @@ impl From<Partial> for Node {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use Bytes;
+    use super::{Node, Rope};
+    use std::thread;
+
+    const DEPTH: usize = 50_000;
+
+    // Builds a rope leaning to the right, one single byte leaf per level,
+    // without the rebalancing done by `Rope::concat`. Byte `i` is `i % 251`.
+    fn chain(depth: usize) -> Bytes {
+        let mut node = Node::from(Bytes::from_slice(&[((depth - 1) % 251) as u8]));
+
+        for i in (0..depth - 1).rev() {
+            let leaf = Bytes::from_slice(&[(i % 251) as u8]);
+            node = Rope::new(leaf, node).into();
+        }
+
+        node.into()
+    }
+
+    #[test]
+    fn test_deep_rope_index_slice_and_drop() {
+        // Recursing once per level would overflow the small stack
+        let child = thread::Builder::new().stack_size(256 * 1024).spawn(|| {
+            let bytes = chain(DEPTH);
+
+            assert_eq!((DEPTH - 1) as u16, bytes.depth());
+            assert_eq!(DEPTH, bytes.len());
+            assert_eq!(0, bytes[0]);
+            assert_eq!(((DEPTH - 1) % 251) as u8, bytes[DEPTH - 1]);
+
+            let slice = bytes.slice(1, DEPTH - 1);
+            drop(bytes);
+
+            assert_eq!(DEPTH - 2, slice.len());
+            assert_eq!(1, slice[0]);
+            assert_eq!(((DEPTH - 2) % 251) as u8, slice[DEPTH - 3]);
+
+            let tail = slice.slice_from(DEPTH / 2);
+            drop(slice);
+
+            assert_eq!(((DEPTH / 2 + 1) % 251) as u8, tail[0]);
+        }).unwrap();
+
+        child.join().unwrap();
+    }
+}
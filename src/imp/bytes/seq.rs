@@ -1,6 +1,6 @@
 //! Immutable set of bytes sequential in memory.
 
-use {MutBuf, Bytes};
+use {MutBufExt, Bytes};
 use buf::{AppendBuf};
 use std::ops;
 use std::io::Cursor;
@@ -12,8 +12,8 @@ mod alloc;
 
 use std::io;
 
-pub use imp::buf::{Buf, MutBuf, IntoBuf};
-pub use imp::bytes::Bytes;
+pub use imp::buf::{Buf, BufExt, MutBuf, MutBufExt, IntoBuf};
+pub use imp::bytes::{Bytes, BytesBuilder, Split, SplitN, Lines};
 
 pub mod buf {
     //! Traits, helpers, and type definitions for working with buffers.
@@ -23,16 +23,29 @@ pub mod buf {
         Sink,
         Reader,
         ReadExt,
+        SeekBuf,
         Writer,
         WriteExt,
+        WhenFull,
+        BufferFull,
         Fmt,
+        FmtExt,
+        TruncatingFmt,
     };
 
     pub use imp::buf::slice_buf::SliceBuf;
     pub use imp::buf::append::AppendBuf;
+    pub use imp::buf::ascii::DecimalError;
+    pub use imp::buf::bits::{BitReader, BitWriter, BitOrder};
+    pub use imp::buf::prefix::{Patch, LengthPrefix, PrefixMark, PrefixError};
     pub use imp::buf::block::{BlockBuf, BlockBufCursor};
+    pub use imp::buf::checkpoint::{Checkpoint, Rewind};
+    pub use imp::buf::cstr::CStrError;
+    pub use imp::buf::endian::{Endian, EndianBuf};
     pub use imp::buf::ring::RingBuf;
+    pub use imp::buf::stream::{ReadBuf, WriteBuf};
     pub use imp::buf::take::Take;
+    pub use imp::buf::uninit::UninitSlice;
     pub use imp::bytes::BytesBuf;
 }
 
@@ -6,11 +6,22 @@ extern crate byteorder;
 
 // == Buf
 mod test_append;
+mod test_ascii;
+mod test_bits;
 mod test_block;
+mod test_bulk;
+mod test_checkpoint;
+mod test_cstr;
+mod test_dyn;
+mod test_endian;
 mod test_buf;
 mod test_buf_fill;
 mod test_slice_buf;
+mod test_stream;
 mod test_mut_buf;
+mod test_num;
+mod test_prefix;
+mod test_reader;
 mod test_ring;
 
 // == Bytes
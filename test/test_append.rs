@@ -1,4 +1,4 @@
-use bytes::{Buf, MutBuf};
+use bytes::{BufExt, MutBuf};
 use bytes::buf::AppendBuf;
 
 #[test]
@@ -0,0 +1,189 @@
+use bytes::{Buf, MutBuf};
+use bytes::buf::{BlockBuf, DecimalError};
+use std::{f32, f64, i64, io, u64};
+
+fn chunked(src: &[u8]) -> BlockBuf {
+    let mut buf = BlockBuf::new(32, 3);
+    buf.write_slice(src);
+    buf
+}
+
+#[test]
+pub fn test_write_decimal_u64() {
+    let cases: &[(u64, &str)] = &[
+        (0, "0"),
+        (7, "7"),
+        (10, "10"),
+        (99, "99"),
+        (100, "100"),
+        (12345, "12345"),
+        (u64::MAX, "18446744073709551615"),
+    ];
+
+    for &(n, expected) in cases {
+        let mut buf = vec![];
+        buf.write_decimal_u64(n);
+        assert_eq!(buf, expected.as_bytes());
+    }
+}
+
+#[test]
+pub fn test_write_decimal_i64() {
+    let cases: &[(i64, &str)] = &[
+        (0, "0"),
+        (-1, "-1"),
+        (42, "42"),
+        (-12345, "-12345"),
+        (i64::MAX, "9223372036854775807"),
+        (i64::MIN, "-9223372036854775808"),
+    ];
+
+    for &(n, expected) in cases {
+        let mut buf = vec![];
+        buf.write_decimal_i64(n);
+        assert_eq!(buf, expected.as_bytes());
+    }
+}
+
+#[test]
+pub fn test_write_hex_u64() {
+    let cases: &[(u64, &str)] = &[
+        (0, "0"),
+        (0xa, "a"),
+        (0xdead_beef, "deadbeef"),
+        (u64::MAX, "ffffffffffffffff"),
+    ];
+
+    for &(n, expected) in cases {
+        let mut buf = vec![];
+        buf.write_hex_u64(n);
+        assert_eq!(buf, expected.as_bytes());
+    }
+}
+
+#[test]
+pub fn test_write_decimal_float() {
+    let cases: &[f64] = &[
+        0.0, -0.0, 1.0, 0.1, 1e-7, 1.5e300, f64::MIN_POSITIVE, -2.2250738585072014e-308,
+        f64::MAX, f64::MIN, f64::INFINITY, f64::NEG_INFINITY];
+
+    for &n in cases {
+        let mut buf = vec![];
+        buf.write_decimal_f64(n);
+        assert_eq!(buf, format!("{:?}", n).as_bytes());
+
+        let parsed: f64 = String::from_utf8(buf).unwrap().parse().unwrap();
+        assert_eq!(parsed.to_bits(), n.to_bits());
+    }
+
+    let mut buf = vec![];
+    buf.write_decimal_f64(f64::NAN);
+    assert_eq!(buf, b"NaN");
+
+    let mut buf = vec![];
+    buf.write_decimal_f32(f32::MIN_POSITIVE);
+    assert_eq!(buf, format!("{:?}", f32::MIN_POSITIVE).as_bytes());
+}
+
+#[test]
+#[should_panic]
+pub fn test_write_decimal_overflow() {
+    let mut dst = [0; 4];
+    let mut buf = io::Cursor::new(&mut dst[..]);
+    buf.write_decimal_u64(12345);
+}
+
+#[test]
+pub fn test_read_decimal_u64() {
+    let mut buf = io::Cursor::new(b"12345 rest");
+    assert_eq!(Ok(12345), buf.read_decimal_u64());
+    assert_eq!(b" rest", buf.bytes());
+
+    let mut buf = io::Cursor::new(b"0");
+    assert_eq!(Ok(0), buf.read_decimal_u64());
+    assert!(!buf.has_remaining());
+
+    let mut buf = io::Cursor::new(b"18446744073709551615\r\n");
+    assert_eq!(Ok(u64::MAX), buf.read_decimal_u64());
+    assert_eq!(b"\r\n", buf.bytes());
+}
+
+#[test]
+pub fn test_read_decimal_no_digits() {
+    let mut buf = io::Cursor::new(b"x1");
+    assert_eq!(Err(DecimalError::NoDigits), buf.read_decimal_u64());
+    assert_eq!(b"x1", buf.bytes());
+
+    let mut buf = io::Cursor::new(b"");
+    assert_eq!(Err(DecimalError::NoDigits), buf.read_decimal_u64());
+
+    let mut buf = io::Cursor::new(b"-x");
+    assert_eq!(Err(DecimalError::NoDigits), buf.read_decimal_i64());
+    assert_eq!(b"x", buf.bytes());
+}
+
+#[test]
+pub fn test_read_decimal_overflow() {
+    let mut buf = io::Cursor::new(b"18446744073709551616;");
+    assert_eq!(Err(DecimalError::Overflow), buf.read_decimal_u64());
+    assert_eq!(b";", buf.bytes());
+
+    let mut buf = io::Cursor::new(b"9223372036854775808");
+    assert_eq!(Err(DecimalError::Overflow), buf.read_decimal_i64());
+
+    let mut buf = io::Cursor::new(b"-9223372036854775809");
+    assert_eq!(Err(DecimalError::Overflow), buf.read_decimal_i64());
+}
+
+#[test]
+pub fn test_read_decimal_i64() {
+    let cases: &[(&[u8], i64)] = &[
+        (b"0", 0),
+        (b"+17", 17),
+        (b"-17", -17),
+        (b"9223372036854775807", i64::MAX),
+        (b"-9223372036854775808", i64::MIN),
+    ];
+
+    for &(src, expected) in cases {
+        let mut buf = io::Cursor::new(src);
+        assert_eq!(Ok(expected), buf.read_decimal_i64());
+        assert!(!buf.has_remaining());
+    }
+}
+
+#[test]
+pub fn test_read_decimal_across_chunks() {
+    let block = chunked(b"-1234567890123,42");
+    let mut buf = block.buf();
+
+    assert_eq!(Ok(-1234567890123), buf.read_decimal_i64());
+    assert_eq!(Some(&b','), buf.bytes().first());
+    buf.advance(1);
+    assert_eq!(Ok(42), buf.read_decimal_u64());
+    assert!(!buf.has_remaining());
+
+    let block = chunked(b"123456789012345678901234567890 ");
+    let mut buf = block.buf();
+
+    assert_eq!(Err(DecimalError::Overflow), buf.read_decimal_u64());
+    assert_eq!(1, buf.remaining());
+}
+
+#[test]
+pub fn test_decimal_round_trip() {
+    let mut dst = vec![];
+
+    for &n in &[0, 1, -1, 1 << 40, i64::MIN, i64::MAX] {
+        dst.write_decimal_i64(n);
+        dst.write_slice(b" ");
+    }
+
+    let block = chunked(&dst);
+    let mut buf = block.buf();
+
+    for &n in &[0, 1, -1, 1 << 40, i64::MIN, i64::MAX] {
+        assert_eq!(Ok(n), buf.read_decimal_i64());
+        buf.advance(1);
+    }
+}
@@ -0,0 +1,215 @@
+use bytes::{Buf, MutBuf, Bytes};
+use bytes::buf::{BitReader, BitWriter, BitOrder, BlockBuf};
+use std::io::Cursor;
+use std::{i64, u64};
+
+/// Values and widths exercising every width, including ones that straddle
+/// several bytes
+fn fields() -> Vec<(u64, u32)> {
+    (0..=64u32).map(|n| {
+        let val = 0x9E37_79B9_7F4A_7C15u64.rotate_left(n) & if n == 64 { !0 } else { (1 << n) - 1 };
+        (val, n)
+    }).collect()
+}
+
+fn write_fields(order: BitOrder) -> Vec<u8> {
+    let mut bits = BitWriter::new(vec![], order);
+
+    for (val, n) in fields() {
+        bits.write_bits(val, n);
+    }
+
+    bits.finish()
+}
+
+fn read_fields<B: Buf>(buf: B, order: BitOrder) {
+    let mut bits = BitReader::new(buf, order);
+
+    for (val, n) in fields() {
+        assert_eq!(val, bits.read_bits(n), "{} bits", n);
+    }
+
+    assert!(bits.align_to_byte() < 8);
+    assert_eq!(0, bits.bits_remaining());
+}
+
+#[test]
+pub fn test_msb_first() {
+    let mut bits = BitWriter::new(vec![], BitOrder::MsbFirst);
+    bits.write_bits(0b101, 3);
+    bits.write_bits(0x1FF, 9);
+    bits.write_bool(true);
+    assert_eq!(3, bits.align_to_byte());
+    assert_eq!(bits.finish(), b"\xBF\xF8");
+
+    let mut bits = BitReader::new(Cursor::new(b"\xBF\xF8"), BitOrder::MsbFirst);
+    assert_eq!(0b101, bits.read_bits(3));
+    assert_eq!(0x1FF, bits.read_bits(9));
+    assert!(bits.read_bool());
+    assert!(!bits.is_aligned());
+    assert_eq!(3, bits.align_to_byte());
+    assert!(bits.is_aligned());
+}
+
+#[test]
+pub fn test_lsb_first() {
+    let mut bits = BitWriter::new(vec![], BitOrder::LsbFirst);
+    bits.write_bits(0b101, 3);
+    bits.write_bits(0x1FF, 9);
+    bits.write_bool(true);
+    assert_eq!(bits.finish(), b"\xFD\x1F");
+
+    let mut bits = BitReader::new(Cursor::new(b"\xFD\x1F"), BitOrder::LsbFirst);
+    assert_eq!(0b101, bits.read_bits(3));
+    assert_eq!(0x1FF, bits.read_bits(9));
+    assert!(bits.read_bool());
+    assert_eq!(3, bits.align_to_byte());
+}
+
+#[test]
+pub fn test_round_trip_all_widths() {
+    for &order in &[BitOrder::MsbFirst, BitOrder::LsbFirst] {
+        let src = write_fields(order);
+        read_fields(Cursor::new(&src), order);
+    }
+}
+
+#[test]
+pub fn test_straddle_block_chunks() {
+    for &order in &[BitOrder::MsbFirst, BitOrder::LsbFirst] {
+        let src = write_fields(order);
+
+        let mut block = BlockBuf::new(src.len() / 3 + 1, 3);
+        block.write_slice(&src);
+
+        read_fields(block.buf(), order);
+    }
+}
+
+#[test]
+pub fn test_straddle_rope_chunks() {
+    for &order in &[BitOrder::MsbFirst, BitOrder::LsbFirst] {
+        let src = write_fields(order);
+
+        let rope = src.chunks(5).fold(Bytes::empty(), |acc, chunk| {
+            acc.concat(&Bytes::from(chunk))
+        });
+
+        read_fields(rope.buf(), order);
+    }
+}
+
+#[test]
+pub fn test_exp_golomb() {
+    // ue(v) codes from the H.264 specification
+    let mut bits = BitWriter::new(vec![], BitOrder::MsbFirst);
+    bits.write_exp_golomb(0); // 1
+    bits.write_exp_golomb(1); // 010
+    bits.write_exp_golomb(2); // 011
+    bits.write_exp_golomb(3); // 00100
+    bits.write_exp_golomb(7); // 0001000
+    assert_eq!(bits.finish(), b"\xA6\x41\x00");
+
+    let mut bits = BitReader::new(Cursor::new(b"\xA6\x41\x00"), BitOrder::MsbFirst);
+    assert_eq!(0, bits.read_exp_golomb());
+    assert_eq!(1, bits.read_exp_golomb());
+    assert_eq!(2, bits.read_exp_golomb());
+    assert_eq!(3, bits.read_exp_golomb());
+    assert_eq!(7, bits.read_exp_golomb());
+
+    // The code bits keep their order in an LSB-first stream
+    let mut bits = BitWriter::new(vec![], BitOrder::LsbFirst);
+    bits.write_exp_golomb(1); // 010
+    bits.write_exp_golomb(3); // 00100
+    assert_eq!(bits.finish(), b"\x22");
+}
+
+#[test]
+pub fn test_exp_golomb_round_trip() {
+    let unsigned = [0, 1, 2, 100, 1 << 40, u64::MAX - 1];
+    let signed = [0, 1, -1, 2, -2, 12345, -12345, i64::MAX, i64::MIN + 1];
+
+    for &order in &[BitOrder::MsbFirst, BitOrder::LsbFirst] {
+        let mut bits = BitWriter::new(vec![], order);
+
+        for &n in &unsigned {
+            bits.write_exp_golomb(n);
+        }
+
+        for &n in &signed {
+            bits.write_signed_exp_golomb(n);
+        }
+
+        let src = bits.finish();
+        let mut block = BlockBuf::new(src.len() / 3 + 1, 3);
+        block.write_slice(&src);
+
+        let mut bits = BitReader::new(block.buf(), order);
+
+        for &n in &unsigned {
+            assert_eq!(n, bits.read_exp_golomb());
+        }
+
+        for &n in &signed {
+            assert_eq!(n, bits.read_signed_exp_golomb());
+        }
+    }
+}
+
+#[test]
+pub fn test_signed_exp_golomb_mapping() {
+    let mut bits = BitWriter::new(vec![], BitOrder::MsbFirst);
+    bits.write_signed_exp_golomb(1);
+    bits.write_signed_exp_golomb(-1);
+    bits.write_signed_exp_golomb(2);
+
+    let mut bits = BitReader::new(Cursor::new(bits.finish()), BitOrder::MsbFirst);
+    assert_eq!(1, bits.read_exp_golomb());
+    assert_eq!(2, bits.read_exp_golomb());
+    assert_eq!(3, bits.read_exp_golomb());
+}
+
+#[test]
+pub fn test_signed_exp_golomb_bounds() {
+    // The longest codes that can be read map to `i64::MAX` and `-i64::MAX`
+    let mut bits = BitWriter::new(vec![], BitOrder::MsbFirst);
+    bits.write_exp_golomb(u64::MAX - 2);
+    bits.write_exp_golomb(u64::MAX - 1);
+
+    let mut bits = BitReader::new(Cursor::new(bits.finish()), BitOrder::MsbFirst);
+    assert_eq!(i64::MAX, bits.read_signed_exp_golomb());
+    assert_eq!(-i64::MAX, bits.read_signed_exp_golomb());
+}
+
+#[test]
+#[should_panic]
+pub fn test_signed_exp_golomb_too_large() {
+    // The code for 2^63 needs 64 leading zeros, which is rejected
+    let mut src = vec![0; 8];
+    src.extend_from_slice(&[0xFF; 9]);
+
+    let mut bits = BitReader::new(Cursor::new(src), BitOrder::MsbFirst);
+    bits.read_signed_exp_golomb();
+}
+
+#[test]
+#[should_panic]
+pub fn test_read_past_end() {
+    let mut bits = BitReader::new(Cursor::new(b"\xFF"), BitOrder::MsbFirst);
+    bits.read_bits(4);
+    bits.read_bits(5);
+}
+
+#[test]
+#[should_panic]
+pub fn test_exp_golomb_too_long() {
+    let mut bits = BitReader::new(Cursor::new([0; 16]), BitOrder::MsbFirst);
+    bits.read_exp_golomb();
+}
+
+#[test]
+#[should_panic]
+pub fn test_write_value_too_wide() {
+    let mut bits = BitWriter::new(vec![], BitOrder::MsbFirst);
+    bits.write_bits(8, 3);
+}
@@ -1,5 +1,5 @@
 use bytes::{MutBuf};
-use bytes::buf::{BlockBuf};
+use bytes::buf::{BlockBuf, UninitSlice};
 
 #[test]
 pub fn test_block_drop() {
@@ -20,3 +20,27 @@ pub fn test_block_drop() {
     assert_eq!(buf.len(), 3);
     assert_eq!(buf.is_compact(), true);
 }
+
+#[test]
+pub fn test_block_release_unused() {
+    let mut buf = BlockBuf::new(4, 8);
+
+    {
+        let mut dst: [&mut UninitSlice; 4] = Default::default();
+        assert_eq!(4, buf.mut_bytes_vec(&mut dst));
+    }
+
+    assert_eq!(buf.is_compact(), false);
+
+    buf.release_unused();
+    assert_eq!(Some(&b""[..]), buf.bytes());
+    assert_eq!(buf.is_compact(), true);
+}
+
+#[test]
+#[should_panic]
+pub fn test_block_advance_without_blocks() {
+    let mut buf = BlockBuf::new(2, 4);
+
+    unsafe { buf.advance(1); }
+}
@@ -1,4 +1,4 @@
-use bytes::{Buf};
+use bytes::{Buf, BufExt};
 use byteorder;
 use std::io::{Cursor};
 use std::vec::{Vec};
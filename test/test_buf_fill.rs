@@ -1,6 +1,6 @@
 use bytes::*;
 use bytes::buf::*;
-use std::io;
+use std::{cmp, io};
 
 #[test]
 pub fn test_readijng_buf_from_reader() {
@@ -11,6 +11,328 @@ pub fn test_readijng_buf_from_reader() {
     assert_eq!(Bytes::from_slice(&b"foo"), Bytes::from(buf));
 }
 
+#[test]
+pub fn test_reading_block_buf_vectored() {
+    let src: Vec<u8> = (0..20).collect();
+    let mut reader = Vectored::new(&src);
+    let mut buf = BlockBuf::new(8, 4);
+
+    // Part of a block is written before the read
+    buf.write_slice(&[0xFF, 0xFF]);
+
+    let n = reader.read_buf(&mut buf).unwrap();
+
+    assert_eq!(1, reader.calls);
+    assert!(reader.slices > 1);
+    assert!(n > buf.len() - n);
+
+    let n = n + reader.read_buf(&mut buf).unwrap();
+    assert_eq!(n, 20);
+    assert!(reader.calls <= 2);
+
+    let mut out = vec![];
+    buf.buf().copy_to(&mut out);
+    assert_eq!(&out[..2], &[0xFF, 0xFF]);
+    assert_eq!(&out[2..], &src[..]);
+}
+
+#[test]
+pub fn test_reading_block_buf_matches_write_slice() {
+    let mut read = BlockBuf::new(16, 8);
+    let mut written = BlockBuf::new(16, 8);
+
+    assert_eq!(5, (&b"hello"[..]).read_buf(&mut read).unwrap());
+    written.write_slice(b"hello");
+
+    assert_eq!(written.bytes(), read.bytes());
+    assert_eq!(Some(&b"hello"[..]), read.bytes());
+    assert_eq!(written.is_compact(), read.is_compact());
+    assert!(read.is_compact());
+}
+
+#[test]
+pub fn test_reading_block_buf_error_releases_blocks() {
+    let mut reader = Scripted::new(vec![Err(io::ErrorKind::WouldBlock)]);
+    let mut buf = BlockBuf::new(16, 8);
+
+    assert!(reader.read_buf(&mut buf).is_err());
+    assert_eq!(Some(&b""[..]), buf.bytes());
+    assert!(buf.is_compact());
+}
+
+#[test]
+pub fn test_reading_ring_buf_vectored() {
+    let mut reader = Vectored::new(b"abcdefghijklmnop");
+    let mut buf = RingBuf::with_capacity(16);
+
+    buf.write_slice(b"0123456789");
+    Buf::advance(&mut buf, 10);
+
+    // The free space wraps around the end of the memory
+    assert_eq!(16, reader.read_buf(&mut buf).unwrap());
+    assert_eq!(1, reader.calls);
+    assert_eq!(2, reader.slices);
+
+    let mut out = vec![];
+    buf.copy_to(&mut out);
+    assert_eq!(out, b"abcdefghijklmnop");
+}
+
+#[test]
+pub fn test_reading_does_not_zero_initialized_memory() {
+    let mut reader = Inspect { seen: vec![] };
+    let mut buf = RingBuf::with_capacity(8);
+
+    buf.write_slice(b"abcdefgh");
+    Buf::advance(&mut buf, 8);
+
+    assert_eq!(0, reader.read_buf(&mut buf).unwrap());
+    assert_eq!(reader.seen, b"abcdefgh");
+}
+
+#[test]
+pub fn test_reading_exposes_only_the_limit() {
+    let mut reader = Inspect { seen: vec![] };
+    let mut buf = Vec::with_capacity(1024);
+
+    let err = reader.read_buf_exact(&mut buf, 5).unwrap_err();
+    assert_eq!(io::ErrorKind::UnexpectedEof, err.kind());
+    assert_eq!(reader.seen, [0; 5]);
+
+    let mut reader = Inspect { seen: vec![] };
+    let mut take = BlockBuf::new(4, 8).take(10);
+
+    assert_eq!(0, reader.read_buf(&mut take).unwrap());
+    assert_eq!(reader.seen.len(), 10);
+}
+
+#[test]
+pub fn test_reading_vec_zeroes_a_chunk() {
+    let mut reader = Inspect { seen: vec![] };
+    let mut buf = Vec::with_capacity(64 * 1024);
+
+    assert_eq!(0, reader.read_buf(&mut buf).unwrap());
+    assert_eq!(reader.seen.len(), 8 * 1024);
+}
+
+#[test]
+pub fn test_reading_take_vectored() {
+    let mut reader = Vectored::new(b"abcdefghijklmnop");
+    let mut take = BlockBuf::new(8, 4).take(6);
+
+    assert_eq!(6, reader.read_buf(&mut take).unwrap());
+    assert_eq!(0, reader.read_buf(&mut take).unwrap());
+
+    let mut out = vec![];
+    take.into_inner().buf().copy_to(&mut out);
+    assert_eq!(out, b"abcdef");
+}
+
+#[test]
+pub fn test_read_buf_exact() {
+    let mut reader = Scripted::new(vec![
+        Ok(&b"hel"[..]),
+        Err(io::ErrorKind::Interrupted),
+        Ok(&b"lo wor"[..]),
+        Ok(&b"ld"[..]),
+    ]);
+    let mut buf = vec![];
+
+    assert_eq!(5, reader.read_buf_exact(&mut buf, 5).unwrap());
+    assert_eq!(buf, b"hello");
+
+    assert_eq!(6, reader.read_buf_exact(&mut buf, 6).unwrap());
+    assert_eq!(buf, b"hello world");
+}
+
+#[test]
+pub fn test_read_buf_exact_would_block() {
+    let mut reader = Scripted::new(vec![
+        Ok(&b"abc"[..]),
+        Err(io::ErrorKind::WouldBlock),
+        Err(io::ErrorKind::WouldBlock),
+        Ok(&b"def"[..]),
+    ]);
+    let mut buf = SliceBuf::with_capacity(16);
+
+    // Progress is returned when the reader blocks part way through
+    assert_eq!(3, reader.read_buf_exact(&mut buf, 6).unwrap());
+
+    // Without progress, the error is returned
+    let err = reader.read_buf_exact(&mut buf, 3).unwrap_err();
+    assert_eq!(io::ErrorKind::WouldBlock, err.kind());
+
+    assert_eq!(3, reader.read_buf_exact(&mut buf, 3).unwrap());
+    assert_eq!(buf.bytes(), b"abcdef");
+}
+
+#[test]
+pub fn test_read_buf_exact_eof() {
+    let mut reader = Scripted::new(vec![Ok(&b"abc"[..])]);
+    let mut buf = vec![];
+
+    let err = reader.read_buf_exact(&mut buf, 4).unwrap_err();
+    assert_eq!(io::ErrorKind::UnexpectedEof, err.kind());
+    assert_eq!(buf, b"abc");
+}
+
+#[test]
+#[should_panic]
+pub fn test_read_buf_exact_overflow() {
+    let mut reader = Scripted::new(vec![]);
+    let mut buf = SliceBuf::with_capacity(4);
+
+    let _ = reader.read_buf_exact(&mut buf, 5);
+}
+
+#[test]
+pub fn test_read_to_end_buf() {
+    let src: Vec<u8> = (0..1000).map(|i| i as u8).collect();
+
+    let mut buf = vec![];
+    assert_eq!(1000, (&src[..]).read_to_end_buf(&mut buf).unwrap());
+    assert_eq!(buf, src);
+
+    let mut buf = BlockBuf::new(64, 32);
+    assert_eq!(1000, (&src[..]).read_to_end_buf(&mut buf).unwrap());
+
+    let mut out = vec![];
+    buf.buf().copy_to(&mut out);
+    assert_eq!(out, src);
+}
+
+#[test]
+pub fn test_read_to_end_buf_would_block() {
+    let mut reader = Scripted::new(vec![
+        Ok(&b"foo"[..]),
+        Err(io::ErrorKind::Interrupted),
+        Ok(&b"bar"[..]),
+        Err(io::ErrorKind::WouldBlock),
+        Ok(&b"baz"[..]),
+    ]);
+    let mut buf = vec![];
+
+    assert_eq!(6, reader.read_to_end_buf(&mut buf).unwrap());
+    assert_eq!(3, reader.read_to_end_buf(&mut buf).unwrap());
+    assert_eq!(0, reader.read_to_end_buf(&mut buf).unwrap());
+    assert_eq!(buf, b"foobarbaz");
+}
+
+#[test]
+pub fn test_read_to_end_buf_full() {
+    let mut buf = SliceBuf::with_capacity(4);
+
+    let err = (&b"hello"[..]).read_to_end_buf(&mut buf).unwrap_err();
+    assert!(err.get_ref().unwrap().is::<BufferFull>());
+    assert_eq!(buf.bytes(), b"hell");
+}
+
+#[test]
+pub fn test_read_to_end_buf_exact_fit() {
+    let src: Vec<u8> = (0..16).collect();
+    let mut buf = SliceBuf::with_capacity(16);
+
+    assert_eq!(16, (&src[..]).read_to_end_buf(&mut buf).unwrap());
+    assert_eq!(buf.bytes(), &src[..]);
+
+    let mut reader = Scripted::new(vec![
+        Ok(&b"abcd"[..]),
+        Err(io::ErrorKind::Interrupted),
+        Ok(&b""[..]),
+    ]);
+    let mut buf = SliceBuf::with_capacity(4);
+
+    assert_eq!(4, reader.read_to_end_buf(&mut buf).unwrap());
+    assert_eq!(buf.bytes(), b"abcd");
+}
+
+/// Returns the scripted chunks and errors from successive reads
+struct Scripted {
+    steps: Vec<Result<&'static [u8], io::ErrorKind>>,
+}
+
+impl Scripted {
+    fn new(steps: Vec<Result<&'static [u8], io::ErrorKind>>) -> Scripted {
+        Scripted { steps: steps }
+    }
+}
+
+impl io::Read for Scripted {
+    fn read(&mut self, dst: &mut [u8]) -> io::Result<usize> {
+        if self.steps.is_empty() {
+            return Ok(0);
+        }
+
+        match self.steps[0] {
+            Ok(src) => {
+                let len = cmp::min(src.len(), dst.len());
+                dst[..len].copy_from_slice(&src[..len]);
+
+                if len < src.len() {
+                    self.steps[0] = Ok(&src[len..]);
+                } else {
+                    self.steps.remove(0);
+                }
+
+                Ok(len)
+            }
+            Err(kind) => {
+                self.steps.remove(0);
+                Err(io::Error::new(kind, "scripted error"))
+            }
+        }
+    }
+}
+
+/// Records the number of vectored reads and of slices passed to them
+struct Vectored<'a> {
+    src: io::Cursor<&'a [u8]>,
+    calls: usize,
+    slices: usize,
+}
+
+impl<'a> Vectored<'a> {
+    fn new(src: &'a [u8]) -> Vectored<'a> {
+        Vectored {
+            src: io::Cursor::new(src),
+            calls: 0,
+            slices: 0,
+        }
+    }
+}
+
+impl<'a> io::Read for Vectored<'a> {
+    fn read(&mut self, dst: &mut [u8]) -> io::Result<usize> {
+        self.src.read(dst)
+    }
+
+    fn read_vectored(&mut self, dst: &mut [io::IoSliceMut]) -> io::Result<usize> {
+        self.calls += 1;
+        self.slices = dst.iter().filter(|s| !s.is_empty()).count();
+        self.src.read_vectored(dst)
+    }
+}
+
+/// Records the contents of the slices it is passed, without reading
+struct Inspect {
+    seen: Vec<u8>,
+}
+
+impl io::Read for Inspect {
+    fn read(&mut self, dst: &mut [u8]) -> io::Result<usize> {
+        self.seen.extend_from_slice(dst);
+        Ok(0)
+    }
+
+    fn read_vectored(&mut self, dst: &mut [io::IoSliceMut]) -> io::Result<usize> {
+        for bytes in dst {
+            self.seen.extend_from_slice(bytes);
+        }
+
+        Ok(0)
+    }
+}
+
 fn chunks(chunks: Vec<&'static [u8]>) -> Chunked {
     Chunked { chunks: chunks }
 }
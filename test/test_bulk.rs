@@ -0,0 +1,111 @@
+use bytes::{Buf, BufExt, MutBuf, MutBufExt};
+use bytes::buf::BlockBuf;
+use byteorder::{BigEndian, LittleEndian};
+use std::io::Cursor;
+
+fn chunked(src: &[u8]) -> BlockBuf {
+    let mut buf = BlockBuf::new(src.len() / 3 + 1, 3);
+    buf.write_slice(src);
+    buf
+}
+
+#[test]
+pub fn test_write_slice_matches_per_element() {
+    let src: Vec<u32> = (0..1_000u32).map(|i| i.wrapping_mul(0x0101_0101)).collect();
+
+    let mut expected = vec![];
+    for &n in &src {
+        expected.write_u32::<BigEndian>(n);
+    }
+
+    let mut buf = vec![];
+    buf.write_u32_slice::<BigEndian>(&src);
+    assert_eq!(buf, expected);
+
+    let mut expected = vec![];
+    for &n in &src {
+        expected.write_u32::<LittleEndian>(n);
+    }
+
+    let mut buf = vec![];
+    buf.write_u32_slice::<LittleEndian>(&src);
+    assert_eq!(buf, expected);
+}
+
+#[test]
+pub fn test_read_into_across_chunks() {
+    let src: Vec<u16> = (0..100).map(|i| i * 0x0102).collect();
+
+    let mut dst = vec![];
+    dst.write_u16_slice::<BigEndian>(&src);
+
+    // Chunks of 3 bytes split most of the integers
+    let block = chunked(&dst);
+    let mut buf = block.buf();
+
+    let mut out = vec![0; 100];
+    buf.read_u16_into::<BigEndian>(&mut out);
+
+    assert_eq!(out, src);
+    assert!(!buf.has_remaining());
+}
+
+#[test]
+pub fn test_u64_round_trip() {
+    let src: Vec<u64> = (0..300u64).map(|i| i.wrapping_mul(0x0102_0304_0506_0708)).collect();
+
+    let mut dst = vec![];
+    dst.write_u64_slice::<LittleEndian>(&src);
+
+    let mut out = vec![0; 300];
+    Cursor::new(&dst).read_u64_into::<LittleEndian>(&mut out);
+    assert_eq!(out, src);
+
+    let mut buf = Cursor::new(&dst);
+    for &n in &src {
+        assert_eq!(n, buf.read_u64::<LittleEndian>());
+    }
+}
+
+#[test]
+pub fn test_float_round_trip() {
+    let src: Vec<f32> = (0..100).map(|i| i as f32 / 3.0).collect();
+
+    let mut dst = vec![];
+    dst.write_f32_slice::<BigEndian>(&src);
+    assert_eq!(dst.len(), 400);
+
+    let mut buf = Cursor::new(&dst);
+    assert_eq!(src[0], buf.read_f32::<BigEndian>());
+    assert_eq!(src[1], buf.read_f32::<BigEndian>());
+
+    let mut out = vec![0.0; 100];
+    Cursor::new(&dst).read_f32_into::<BigEndian>(&mut out);
+    assert_eq!(out, src);
+
+    let src: Vec<f64> = (0..100).map(|i| -(i as f64) / 7.0).collect();
+
+    let mut dst = vec![];
+    dst.write_f64_slice::<LittleEndian>(&src);
+
+    let block = chunked(&dst);
+    let mut out = vec![0.0; 100];
+    block.buf().read_f64_into::<LittleEndian>(&mut out);
+    assert_eq!(out, src);
+}
+
+#[test]
+#[should_panic]
+pub fn test_read_into_underflow() {
+    let mut buf = Cursor::new(b"\x00\x01\x00");
+    let mut out = [0; 2];
+    buf.read_u16_into::<BigEndian>(&mut out);
+}
+
+#[test]
+#[should_panic]
+pub fn test_write_slice_overflow() {
+    let mut dst = [0; 7];
+    let mut buf = Cursor::new(&mut dst[..]);
+    buf.write_u32_slice::<BigEndian>(&[1, 2]);
+}
@@ -1,4 +1,5 @@
 use bytes::*;
+use bytes::buf::BlockBuf;
 
 #[test]
 pub fn test_debug_short_str_valid_ascii() {
@@ -40,3 +41,164 @@ pub fn test_short_string_invalid_ascii() {
 
     assert_eq!(d, "Bytes[len=11; foo\\x00bar\\xFFbaz]");
 }
+
+#[test]
+pub fn test_from_iter_u8() {
+    let b: Bytes = (0..200u32).map(|i| i as u8).collect();
+    assert_eq!(200, b.len());
+
+    for i in 0..200 {
+        assert_eq!(i as u8, b[i]);
+    }
+
+    let b: Bytes = b"abc".iter().cloned().collect();
+    assert_eq!(format!("{:?}", b), "Bytes[len=3; abc]");
+}
+
+#[test]
+pub fn test_from_buf_copy() {
+    use std::io::Cursor;
+
+    let b = Bytes::from_buf(Cursor::new(&b"hello world"[..]));
+    assert_eq!(11, b.len());
+    assert_eq!(format!("{:?}", b), "Bytes[len=11; hello world]");
+
+    let b = Bytes::from_buf(Cursor::new(&b"hello world"[..]).take(5));
+    assert_eq!(format!("{:?}", b), "Bytes[len=5; hello]");
+}
+
+#[test]
+pub fn test_from_buf_bytes() {
+    let src: Vec<u8> = (0..1_000u32).map(|i| i as u8).collect();
+    let rope = Bytes::from(&src[..400]).concat(&Bytes::from(&src[400..]));
+
+    let mut buf = rope.buf();
+    buf.advance(150);
+
+    let b = Bytes::from_buf(buf.take(500));
+    assert_eq!(500, b.len());
+
+    for i in 0..500 {
+        assert_eq!(src[150 + i], b[i]);
+    }
+}
+
+#[test]
+pub fn test_from_buf_block_buf_cursor() {
+    let mut block = BlockBuf::new(4, 16);
+    let src: Vec<u8> = (0..60u32).map(|i| i as u8).collect();
+
+    block.write_slice(&src);
+
+    let mut buf = block.buf();
+    buf.advance(10);
+
+    let b = Bytes::from_buf(buf.take(40));
+    assert_eq!(40, b.len());
+
+    for i in 0..40 {
+        assert_eq!(src[10 + i], b[i]);
+    }
+}
+
+// Returns the same content as a contiguous value and as a rope with a chunk
+// boundary at `mid`.
+fn variants(src: &[u8], mid: usize) -> Vec<Bytes> {
+    // Pad both halves past the concat-by-copy threshold so that a rope is
+    // built, then slice the padding back off.
+    let mut left = vec![b'-'; 128];
+    left.extend_from_slice(&src[..mid]);
+
+    let mut right = src[mid..].to_vec();
+    right.extend_from_slice(&[b'-'; 128]);
+
+    let rope = Bytes::from(left).concat(&Bytes::from(right))
+        .slice(128, 128 + src.len());
+
+    vec![Bytes::from(src), rope]
+}
+
+fn to_vec(b: &Bytes) -> Vec<u8> {
+    let mut dst = vec![];
+    b.buf().copy_to(&mut dst);
+    dst
+}
+
+#[test]
+pub fn test_starts_ends_with() {
+    for b in variants(b"GET /index.html HTTP/1.1", 7) {
+        assert!(b.starts_with(b""));
+        assert!(b.starts_with(b"GET"));
+        assert!(b.starts_with(b"GET /index"));
+        assert!(!b.starts_with(b"POST"));
+        assert!(b.ends_with(b"HTTP/1.1"));
+        assert!(b.ends_with(b"index.html HTTP/1.1"));
+        assert!(!b.ends_with(b"HTTP/1.0"));
+        assert!(!b.starts_with(b"GET /index.html HTTP/1.1 and more"));
+    }
+}
+
+#[test]
+pub fn test_contains() {
+    for b in variants(b"hello world, goodbye world", 9) {
+        assert!(b.contains(b""));
+        assert!(b.contains(b"hello"));
+        assert!(b.contains(b"o wor"));
+        assert!(b.contains(b"ld, g"));
+        assert!(b.contains(b"goodbye world"));
+        assert!(!b.contains(b"worlds"));
+        assert!(!b.contains(b"hello world, goodbye world!"));
+    }
+}
+
+#[test]
+pub fn test_trim_ascii() {
+    for b in variants(b" \t foo bar \r\n", 5) {
+        assert_eq!(to_vec(&b.trim_ascii()), b"foo bar");
+    }
+
+    assert!(Bytes::from_slice(b" \r\n ").trim_ascii().is_empty());
+    assert!(Bytes::empty().trim_ascii().is_empty());
+}
+
+#[test]
+pub fn test_eq_ignore_ascii_case() {
+    for b in variants(b"Content-Length", 4) {
+        assert!(b.eq_ignore_ascii_case(b"content-length"));
+        assert!(b.eq_ignore_ascii_case(b"CONTENT-LENGTH"));
+        assert!(!b.eq_ignore_ascii_case(b"content-type"));
+        assert!(!b.eq_ignore_ascii_case(b"content-lengths"));
+    }
+}
+
+#[test]
+pub fn test_split() {
+    for b in variants(b"a,bc,,def,", 3) {
+        let parts: Vec<Vec<u8>> = b.split(b',').map(|b| to_vec(&b)).collect();
+        let expected: Vec<&[u8]> = vec![b"a", b"bc", b"", b"def", b""];
+        assert_eq!(parts, expected);
+
+        let parts: Vec<Vec<u8>> = b.splitn(2, b',').map(|b| to_vec(&b)).collect();
+        let expected: Vec<&[u8]> = vec![b"a", b"bc,,def,"];
+        assert_eq!(parts, expected);
+    }
+
+    assert_eq!(1, Bytes::empty().split(b',').count());
+    assert_eq!(0, Bytes::from_slice(b"a,b").splitn(0, b',').count());
+}
+
+#[test]
+pub fn test_lines() {
+    for b in variants(b"foo\r\nbar\n\nbaz\n", 4) {
+        let lines: Vec<Vec<u8>> = b.lines().map(|b| to_vec(&b)).collect();
+        let expected: Vec<&[u8]> = vec![b"foo", b"bar", b"", b"baz"];
+        assert_eq!(lines, expected);
+    }
+
+    assert_eq!(0, Bytes::empty().lines().count());
+    assert_eq!(2, Bytes::from_slice(b"foo\nbar").lines().count());
+
+    // A `\r` that is not followed by `\n` is part of the line
+    let lines: Vec<Vec<u8>> = Bytes::from_slice(b"a\r").lines().map(|b| to_vec(&b)).collect();
+    assert_eq!(lines, vec![b"a\r".to_vec()]);
+}
@@ -0,0 +1,146 @@
+use bytes::{Buf, MutBuf, Bytes, BytesBuilder};
+use bytes::buf::{BlockBuf, Checkpoint, RingBuf, SliceBuf, Take};
+use std::io::Cursor;
+
+/// Read `n` bytes, rewind, and check that the same bytes are read again
+fn check_rewind<B: Checkpoint>(buf: &mut B, n: usize) {
+    let rem = buf.remaining();
+    let mark = buf.checkpoint();
+
+    let mut first = vec![0; n];
+    buf.read_slice(&mut first);
+    assert_eq!(rem - n, buf.remaining());
+
+    buf.rewind(mark);
+    assert_eq!(rem, buf.remaining());
+
+    let mut second = vec![0; n];
+    buf.read_slice(&mut second);
+    assert_eq!(first, second);
+}
+
+#[test]
+pub fn test_checkpoint_cursor() {
+    let mut buf = Cursor::new(b"hello world");
+    buf.advance(2);
+
+    check_rewind(&mut buf, 5);
+    assert_eq!(7, buf.position());
+}
+
+#[test]
+pub fn test_checkpoint_slice_buf() {
+    let mut buf = SliceBuf::with_capacity(16);
+    buf.write_slice(b"hello world");
+
+    check_rewind(&mut buf, 11);
+}
+
+#[test]
+pub fn test_checkpoint_ring_buf() {
+    let mut buf = RingBuf::with_capacity(8);
+    buf.write_slice(b"abcdef");
+    Buf::advance(&mut buf, 4);
+    buf.write_slice(b"ghij");
+
+    // The readable bytes wrap around the end of the memory
+    check_rewind(&mut buf, 5);
+}
+
+#[test]
+#[should_panic]
+pub fn test_checkpoint_ring_buf_overwritten() {
+    let mut buf = RingBuf::with_capacity(4);
+    buf.write_slice(b"abcd");
+
+    let mark = buf.checkpoint();
+    Buf::advance(&mut buf, 2);
+    buf.write_slice(b"ef");
+
+    buf.rewind(mark);
+}
+
+#[test]
+pub fn test_checkpoint_bytes_buf() {
+    let bytes = Bytes::from(&b"hello world"[..]);
+    check_rewind(&mut bytes.buf(), 8);
+
+    let mut builder = BytesBuilder::new();
+    builder.push(Bytes::from(&[1; 200][..]));
+    builder.push(Bytes::from(&[2; 200][..]));
+    builder.push(Bytes::from(&[3; 200][..]));
+
+    let rope = builder.build();
+    let mut buf = rope.buf();
+    buf.advance(150);
+
+    check_rewind(&mut buf, 300);
+}
+
+#[test]
+pub fn test_checkpoint_block_buf_cursor() {
+    let mut block = BlockBuf::new(16, 3);
+    block.write_slice(b"hello world");
+
+    let mut buf = block.buf();
+    buf.advance(1);
+
+    check_rewind(&mut buf, 7);
+}
+
+#[test]
+pub fn test_checkpoint_take() {
+    let mut buf = Take::new(Cursor::new(b"hello world"), 8);
+
+    check_rewind(&mut buf, 6);
+    assert_eq!(2, buf.limit());
+    assert_eq!(6, buf.get_ref().position());
+}
+
+#[test]
+pub fn test_guard_rewinds_on_drop() {
+    let mut buf = Cursor::new(b"hello world");
+
+    {
+        let mut guard = buf.guard();
+        guard.advance(6);
+        assert_eq!(b'w', guard.read_u8());
+    }
+
+    assert_eq!(0, buf.position());
+}
+
+#[test]
+pub fn test_guard_commit() {
+    let mut buf = SliceBuf::with_capacity(16);
+    buf.write_slice(b"hello world");
+
+    {
+        let mut guard = buf.guard();
+        Buf::advance(&mut *guard, 6);
+        guard.commit();
+    }
+
+    assert_eq!(b"world", buf.bytes());
+}
+
+#[test]
+pub fn test_guard_nested() {
+    let mut buf = Cursor::new(b"hello world");
+
+    {
+        let mut outer = buf.guard();
+        outer.advance(2);
+
+        {
+            let mut inner = outer.guard();
+            inner.advance(4);
+        }
+
+        assert_eq!(2, outer.position());
+        outer.advance(1);
+        outer.commit();
+    }
+
+    assert_eq!(3, buf.position());
+}
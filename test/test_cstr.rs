@@ -0,0 +1,129 @@
+use bytes::{Buf, BufExt, MutBuf, Bytes};
+use bytes::buf::{BlockBuf, CStrError};
+use std::io::Cursor;
+
+fn to_vec(bytes: &Bytes) -> Vec<u8> {
+    let mut vec = vec![];
+    bytes.buf().copy_to(&mut vec);
+    vec
+}
+
+#[test]
+pub fn test_read_cstr() {
+    let mut buf = Cursor::new(b"hello\0\0world\0");
+
+    assert_eq!(b"hello", &to_vec(&buf.read_cstr(16).unwrap())[..]);
+    assert_eq!(0, buf.read_cstr(16).unwrap().len());
+    assert_eq!(b"world", &to_vec(&buf.read_cstr(5).unwrap())[..]);
+    assert!(!buf.has_remaining());
+}
+
+#[test]
+pub fn test_read_cstr_zero_copy() {
+    let src = Bytes::from(&b"lo\0eth0\0"[..]);
+    let mut buf = src.buf();
+
+    let lo = buf.read_cstr(16).unwrap();
+    assert_eq!(b"lo", &to_vec(&lo)[..]);
+    assert_eq!(lo.buf().bytes().as_ptr(), src.buf().bytes().as_ptr());
+    assert_eq!(b"eth0\0", buf.bytes());
+}
+
+#[test]
+pub fn test_read_cstr_across_chunks() {
+    let mut block = BlockBuf::new(8, 3);
+    block.write_slice(b"abcdefgh\0ij\0");
+
+    let mut buf = block.buf();
+    assert_eq!(b"abcdefgh", &to_vec(&buf.read_cstr(8).unwrap())[..]);
+    assert_eq!(b"ij", &to_vec(&buf.read_cstr(8).unwrap())[..]);
+    assert!(!buf.has_remaining());
+
+    let rope = Bytes::from(&b"abc"[..])
+        .concat(&Bytes::from(&b"def"[..]))
+        .concat(&Bytes::from(&b"g\0h"[..]));
+
+    let mut buf = rope.buf();
+    assert_eq!(b"abcdefg", &to_vec(&buf.read_cstr(100).unwrap())[..]);
+    assert_eq!(b"h", buf.bytes());
+}
+
+#[test]
+pub fn test_read_cstr_errors() {
+    let mut buf = Cursor::new(b"no terminator");
+    assert_eq!(Err(CStrError::NoTerminator), buf.read_cstr(100));
+    assert_eq!(13, buf.remaining());
+
+    let mut buf = Cursor::new(b"");
+    assert_eq!(Err(CStrError::NoTerminator), buf.read_cstr(100));
+
+    let mut buf = Cursor::new(b"toolong\0");
+    assert_eq!(Err(CStrError::TooLong), buf.read_cstr(6));
+    assert_eq!(8, buf.remaining());
+    assert_eq!(b"toolong", &to_vec(&buf.read_cstr(7).unwrap())[..]);
+
+    let mut block = BlockBuf::new(8, 3);
+    block.write_slice(b"abcdefgh\0");
+
+    let mut buf = block.buf();
+    assert_eq!(Err(CStrError::TooLong), buf.read_cstr(7));
+    assert_eq!(9, buf.remaining());
+
+    let mut buf = block.buf().take(8);
+    assert_eq!(Err(CStrError::NoTerminator), buf.read_cstr(100));
+    assert_eq!(8, buf.remaining());
+}
+
+#[test]
+pub fn test_read_cstr_retry() {
+    let mut block = BlockBuf::new(4, 64);
+    block.write_slice(&[b'a'; 150]);
+
+    {
+        let mut buf = block.buf();
+        assert_eq!(Err(CStrError::NoTerminator), buf.read_cstr(1000));
+        assert_eq!(150, buf.remaining());
+    }
+
+    block.write_slice(b"\0");
+
+    let mut buf = block.buf();
+    assert_eq!(vec![b'a'; 150], to_vec(&buf.read_cstr(1000).unwrap()));
+    assert!(!buf.has_remaining());
+}
+
+#[test]
+pub fn test_peek_cstr() {
+    let mut buf = Cursor::new(b"abc\0def");
+
+    {
+        let s = buf.peek_cstr().unwrap();
+        assert_eq!(b"abc", s.to_bytes());
+    }
+
+    buf.advance(4);
+    assert!(buf.peek_cstr().is_none());
+
+    let mut block = BlockBuf::new(4, 3);
+    block.write_slice(b"abcd\0");
+    assert!(block.buf().peek_cstr().is_none());
+}
+
+#[test]
+pub fn test_write_cstr() {
+    let mut buf = vec![];
+    buf.write_cstr(b"hello").unwrap();
+    buf.write_cstr(b"").unwrap();
+    assert_eq!(buf, b"hello\0\0");
+
+    assert_eq!(Err(CStrError::InteriorNul), buf.write_cstr(b"a\0b"));
+    assert_eq!(buf, b"hello\0\0");
+}
+
+#[test]
+#[should_panic]
+pub fn test_write_cstr_overflow() {
+    let mut dst = [0; 5];
+    let mut buf = Cursor::new(&mut dst[..]);
+    let _ = buf.write_cstr(b"hello");
+}
@@ -0,0 +1,119 @@
+use bytes::{Buf, BufExt, MutBuf, MutBufExt};
+use bytes::buf::{FmtExt, RingBuf, SliceBuf};
+use byteorder::{BigEndian, LittleEndian};
+use std::io::Cursor;
+
+#[test]
+pub fn test_dyn_buf_read_numbers() {
+    let mut src = Cursor::new(b"\x00\x01\x02\x00\x00\x00\x03\x04hello");
+    let buf: &mut dyn Buf = &mut src;
+
+    assert_eq!(1, buf.read_u16::<BigEndian>());
+    assert_eq!(2, buf.read_u32::<LittleEndian>());
+    assert_eq!(0x0304, buf.read_uint::<BigEndian>(2));
+    assert_eq!(5, buf.remaining());
+    assert_eq!(8, src.position());
+}
+
+#[test]
+pub fn test_dyn_buf_generic_helpers() {
+    fn read_len(buf: &mut dyn Buf) -> u16 {
+        buf.read_u16::<BigEndian>()
+    }
+
+    fn write_len(buf: &mut dyn MutBuf, n: u16) {
+        buf.write_u16::<BigEndian>(n)
+    }
+
+    let mut dst = vec![];
+    write_len(&mut dst, 5);
+    assert_eq!(dst, b"\x00\x05");
+
+    let mut src = Cursor::new(dst);
+    assert_eq!(5, read_len(&mut src));
+}
+
+#[test]
+pub fn test_boxed_buf() {
+    let mut buf: Box<dyn Buf + Send> = Box::new(Cursor::new(b"\x00\x2Aworld".to_vec()));
+
+    assert_eq!(42, buf.read_u16::<BigEndian>());
+    assert_eq!(b"world", buf.bytes());
+
+    let mut dst = vec![];
+    assert_eq!(5, buf.copy_to(&mut dst));
+    assert_eq!(dst, b"world");
+    assert!(!buf.has_remaining());
+}
+
+#[test]
+pub fn test_dyn_buf_adapters() {
+    let mut src = Cursor::new(b"hello world");
+    let mut buf: &mut dyn Buf = &mut src;
+
+    {
+        let mut take = (&mut buf).take(5);
+        let mut dst = [0; 5];
+        take.read_slice(&mut dst);
+        assert_eq!(&dst, b"hello");
+        assert!(!take.has_remaining());
+    }
+
+    assert_eq!(b" world", buf.bytes());
+}
+
+#[test]
+pub fn test_dyn_mut_buf_write_numbers() {
+    let mut dst = vec![];
+
+    {
+        let buf: &mut dyn MutBuf = &mut dst;
+
+        buf.write_u16::<BigEndian>(1);
+        buf.write_u32::<LittleEndian>(2);
+        buf.write_int::<BigEndian>(-2, 3);
+        write!(buf, "{}", 42).unwrap();
+    }
+
+    assert_eq!(dst, b"\x00\x01\x02\x00\x00\x00\xFF\xFF\xFE42");
+}
+
+#[test]
+pub fn test_dyn_mut_buf_copy_from() {
+    let mut buf: Box<dyn MutBuf> = Box::new(vec![]);
+    let mut src = Cursor::new(b"hello");
+    let rem = MutBuf::remaining(&buf);
+
+    assert_eq!(5, buf.copy_from(&mut src));
+    buf.write_u16::<BigEndian>(0x2021);
+    assert_eq!(rem - 7, MutBuf::remaining(&buf));
+}
+
+#[test]
+pub fn test_dyn_mut_buf_forwarding() {
+    let mut ring = RingBuf::with_capacity(16);
+
+    {
+        let mut buf = &mut ring;
+        buf.write_u16::<BigEndian>(0x2021);
+        MutBuf::write_slice(&mut buf, b"hello");
+    }
+
+    assert_eq!(7, Buf::remaining(&ring));
+    assert_eq!(b" !hello", Buf::bytes(&ring));
+}
+
+#[test]
+pub fn test_dyn_copy_between_objects() {
+    let mut src = Cursor::new(b"hello world".to_vec());
+    let mut dst = SliceBuf::with_capacity(32);
+
+    {
+        let buf: &mut dyn Buf = &mut src;
+        let out: &mut dyn MutBuf = &mut dst;
+
+        assert_eq!(11, out.copy_from(buf));
+    }
+
+    assert_eq!(b"hello world", Buf::bytes(&dst));
+}
@@ -0,0 +1,157 @@
+use bytes::{Buf, BufExt, MutBuf, MutBufExt};
+use bytes::buf::{BlockBuf, Checkpoint, Endian, EndianBuf, RingBuf, SeekBuf};
+use byteorder::{BigEndian, LittleEndian, NativeEndian};
+use std::io::Cursor;
+
+#[test]
+pub fn test_endian_resolve() {
+    assert!(Endian::Big.is_big());
+    assert!(Endian::Little.is_little());
+    assert_eq!(Endian::Native.is_big(), cfg!(target_endian = "big"));
+}
+
+#[test]
+pub fn test_read_endian() {
+    let src = b"\x01\x02\x03\x04\x05\x06\x07\x08";
+
+    let mut buf = Cursor::new(&src[..]);
+    assert_eq!(0x0102, buf.read_u16_endian(Endian::Big));
+    assert_eq!(0x0403, buf.read_u16_endian(Endian::Little));
+    assert_eq!(0x0807_0605, buf.read_u32_endian(Endian::Little));
+
+    let mut buf = Cursor::new(&src[..]);
+    assert_eq!(0x0102_0304_0506_0708, buf.read_u64_endian(Endian::Big));
+
+    let mut buf = Cursor::new(&src[..]);
+    assert_eq!(Cursor::new(&src[..]).read_u64::<NativeEndian>(),
+               buf.read_u64_endian(Endian::Native));
+
+    let mut buf = Cursor::new(b"\xFF\xFE\x00\x00\x80\x3F");
+    assert_eq!(-2, buf.read_i16_endian(Endian::Big));
+    assert_eq!(1.0, buf.read_f32_endian(Endian::Little));
+}
+
+#[test]
+pub fn test_write_endian() {
+    let mut buf = vec![];
+    buf.write_u32_endian(0x0102_0304, Endian::Big);
+    buf.write_u32_endian(0x0102_0304, Endian::Little);
+    buf.write_uint_endian(0x0A0B0C, 3, Endian::Big);
+    buf.write_i64_endian(-1, Endian::Little);
+    buf.write_f64_endian(1.0, Endian::Big);
+
+    let mut expected = vec![];
+    expected.write_u32::<BigEndian>(0x0102_0304);
+    expected.write_u32::<LittleEndian>(0x0102_0304);
+    expected.write_uint::<BigEndian>(0x0A0B0C, 3);
+    expected.write_i64::<LittleEndian>(-1);
+    expected.write_f64::<BigEndian>(1.0);
+
+    assert_eq!(buf, expected);
+}
+
+/// A parser that does not know the byte order of its input
+fn parse_header(buf: &mut EndianBuf<Cursor<&[u8]>>) -> (u16, u32) {
+    (buf.read_u16(), buf.read_u32())
+}
+
+#[test]
+pub fn test_endian_buf_read() {
+    let big: &[u8] = b"\x00\x2A\x00\x00\x00\x08";
+    let little: &[u8] = b"\x2A\x00\x08\x00\x00\x00";
+
+    assert_eq!((42, 8), parse_header(&mut EndianBuf::new(Cursor::new(big), Endian::Big)));
+    assert_eq!((42, 8), parse_header(&mut EndianBuf::new(Cursor::new(little), Endian::Little)));
+}
+
+#[test]
+pub fn test_endian_buf_write() {
+    let mut buf = EndianBuf::new(vec![], Endian::Little);
+    buf.write_u16(1);
+    buf.write_i32(-2);
+
+    buf.set_endian(Endian::Big);
+    buf.write_u16(1);
+    buf.write_slice(b"!");
+
+    assert_eq!(buf.into_inner(), b"\x01\x00\xFE\xFF\xFF\xFF\x00\x01!");
+}
+
+#[test]
+pub fn test_endian_buf_ring() {
+    // RingBuf is both a Buf and a MutBuf
+    let mut buf = EndianBuf::new(RingBuf::with_capacity(16), Endian::Big);
+    buf.write_u32(0xDEAD_BEEF);
+    buf.write_f32(-0.5);
+
+    assert_eq!(0xDEAD_BEEF, buf.read_u32());
+    assert_eq!(-0.5, buf.read_f32());
+}
+
+#[test]
+pub fn test_endian_buf_across_chunks() {
+    let mut block = BlockBuf::new(4, 3);
+    block.write_slice(b"\x01\x02\x03\x04\x05\x06");
+
+    let mut buf = EndianBuf::new(block.buf(), Endian::Little);
+    buf.advance(1);
+    assert_eq!(0x0504_0302, buf.read_u32());
+    assert_eq!(1, Buf::remaining(&buf));
+}
+
+#[test]
+pub fn test_endian_wide_and_narrow() {
+    for &(endian, big) in &[(Endian::Big, true), (Endian::Little, false)] {
+        let mut buf = vec![];
+        buf.write_u24_endian(0x0A_0B0C, endian);
+        buf.write_i24_endian(-2, endian);
+        buf.write_u128_endian(1 << 100 | 7, endian);
+        buf.write_i128_endian(-3, endian);
+        buf.write_f16_endian(1.5, endian);
+
+        let mut expected = vec![];
+
+        if big {
+            expected.write_u24::<BigEndian>(0x0A_0B0C);
+            expected.write_i24::<BigEndian>(-2);
+            expected.write_u128::<BigEndian>(1 << 100 | 7);
+            expected.write_i128::<BigEndian>(-3);
+            expected.write_f16::<BigEndian>(1.5);
+        } else {
+            expected.write_u24::<LittleEndian>(0x0A_0B0C);
+            expected.write_i24::<LittleEndian>(-2);
+            expected.write_u128::<LittleEndian>(1 << 100 | 7);
+            expected.write_i128::<LittleEndian>(-3);
+            expected.write_f16::<LittleEndian>(1.5);
+        }
+
+        assert_eq!(buf, expected);
+
+        let mut buf = EndianBuf::new(Cursor::new(buf), endian);
+        assert_eq!(0x0A_0B0C, buf.read_u24());
+        assert_eq!(-2, buf.read_i24());
+        assert_eq!(1 << 100 | 7, buf.read_u128());
+        assert_eq!(-3, buf.read_i128());
+        assert_eq!(1.5, buf.read_f16());
+        assert!(!Buf::has_remaining(&buf));
+    }
+}
+
+#[test]
+pub fn test_endian_buf_seek_and_rewind() {
+    let src: &[u8] = b"\x00\x01\x00\x02";
+    let mut buf = EndianBuf::new(Cursor::new(src), Endian::Big);
+
+    let mark = buf.checkpoint();
+    assert_eq!(1, buf.read_u16());
+    buf.rewind(mark);
+    assert_eq!(0, buf.position());
+
+    buf.set_position(2);
+    assert_eq!(2, buf.read_u16());
+
+    let mut dst = [0; 2];
+    buf.set_position(1);
+    buf.read_slice(&mut dst);
+    assert_eq!(&dst, b"\x01\x00");
+}
@@ -1,22 +1,22 @@
-use bytes::MutBuf;
+use bytes::{MutBuf, MutBufExt};
+use bytes::buf::{BlockBuf, SliceBuf, RingBuf, Writer, WhenFull, BufferFull, Fmt, FmtExt, TruncatingFmt, UninitSlice};
 use byteorder;
-use std::usize;
+use std::isize;
 
 #[test]
 pub fn test_vec_as_mut_buf() {
     let mut buf = Vec::with_capacity(64);
 
-    assert_eq!(buf.remaining(), usize::MAX);
+    assert_eq!(buf.remaining(), isize::MAX as usize);
+    assert!(!buf.is_bounded());
 
-    unsafe {
-        assert!(buf.mut_bytes().len() >= 64);
-    }
+    assert!(buf.mut_bytes().len() >= 64);
 
     buf.copy_from(&b"zomg"[..]);
 
     assert_eq!(&buf, b"zomg");
 
-    assert_eq!(buf.remaining(), usize::MAX - 4);
+    assert_eq!(buf.remaining(), isize::MAX as usize - 4);
     assert_eq!(buf.capacity(), 64);
 
     for _ in 0..16 {
@@ -43,3 +43,261 @@ fn test_write_u16() {
     buf.write_u16::<byteorder::LittleEndian>(8532);
     assert_eq!(b"\x54\x21", &buf[..]);
 }
+
+#[test]
+pub fn test_mut_bytes_write() {
+    let mut buf = SliceBuf::with_capacity(16);
+
+    {
+        let dst = buf.mut_bytes();
+        assert_eq!(16, dst.len());
+
+        dst[..3].copy_from_slice(b"abc");
+        dst.write_byte(3, b'd');
+    }
+
+    unsafe { buf.advance(4); }
+
+    assert_eq!(12, MutBuf::remaining(&buf));
+    assert_eq!(b"abcd", bytes::Buf::bytes(&buf));
+}
+
+#[test]
+pub fn test_put_slice() {
+    let mut buf = SliceBuf::with_capacity(8);
+
+    assert_eq!(5, buf.put_slice(b"hello"));
+    assert_eq!(3, buf.put_slice(b" world"));
+    assert_eq!(0, buf.put_slice(b"!"));
+    assert_eq!(b"hello wo", bytes::Buf::bytes(&buf));
+}
+
+#[test]
+pub fn test_put_slice_across_blocks() {
+    let mut buf = BlockBuf::new(3, 4);
+
+    assert_eq!(10, buf.put_slice(b"0123456789"));
+    assert_eq!(2, buf.put_slice(b"abcdef"));
+    assert_eq!(12, buf.len());
+    assert_eq!(0, MutBuf::remaining(&buf));
+    assert_eq!(0, buf.mut_bytes().len());
+}
+
+#[test]
+pub fn test_put_bytes() {
+    let mut buf = RingBuf::with_capacity(8);
+
+    buf.put_bytes(b'x', 3);
+    buf.put_bytes(b'y', 5);
+
+    assert_eq!(b"xxxyyyyy", bytes::Buf::bytes(&buf));
+}
+
+#[test]
+#[should_panic]
+pub fn test_put_bytes_overflow() {
+    let mut buf = SliceBuf::with_capacity(4);
+    buf.put_bytes(0, 5);
+}
+
+#[test]
+pub fn test_vec_reserve() {
+    let mut buf: Vec<u8> = Vec::new();
+
+    MutBuf::reserve(&mut buf, 100);
+    assert!(buf.capacity() >= 100);
+    assert!(buf.mut_bytes().len() >= 100);
+
+    buf.write_slice(&[1; 100]);
+    let cap = buf.capacity();
+
+    // Growing at least doubles the capacity
+    buf.write_u8(2);
+    assert!(buf.capacity() >= 2 * cap);
+    assert_eq!(101, buf.len());
+}
+
+#[test]
+#[should_panic]
+pub fn test_vec_advance_past_capacity() {
+    let mut buf: Vec<u8> = Vec::with_capacity(8);
+    let cap = buf.capacity();
+
+    unsafe { buf.advance(cap + 1); }
+}
+
+#[test]
+pub fn test_growable_slice_buf() {
+    let mut buf = SliceBuf::growable(4);
+
+    assert!(!buf.is_bounded());
+    assert_eq!(4, buf.capacity());
+
+    buf.write_slice(b"hello world");
+    assert_eq!(64, buf.capacity());
+
+    for _ in 0..60 {
+        buf.write_u8(b'!');
+    }
+
+    // The capacity doubles
+    assert_eq!(128, buf.capacity());
+    assert_eq!(71, buf.len());
+    assert!(bytes::Buf::bytes(&buf).starts_with(b"hello world!!!"));
+
+    let bounded = SliceBuf::with_capacity(4);
+    assert!(bounded.is_bounded());
+    assert_eq!(4, MutBuf::remaining(&bounded));
+}
+
+#[test]
+pub fn test_writer_growable_no_short_writes() {
+    use std::io::Write;
+
+    let src: Vec<u8> = (0..10_000u32).map(|i| i as u8).collect();
+
+    let mut writer = Writer::new(SliceBuf::growable(16));
+    assert_eq!(10_000, writer.write(&src).unwrap());
+    assert_eq!(&src[..], bytes::Buf::bytes(writer.get_ref()));
+
+    let mut writer = Writer::new(Vec::new());
+    writer.write_all(&src).unwrap();
+    assert_eq!(src, writer.into_inner());
+
+    let mut writer = Writer::new(SliceBuf::with_capacity(16));
+    assert_eq!(16, writer.write(&src).unwrap());
+}
+
+#[test]
+pub fn test_writer_when_full() {
+    use std::io::{ErrorKind, Write};
+
+    let mut writer = Writer::new(SliceBuf::with_capacity(4));
+    assert_eq!(WhenFull::Grow, writer.when_full());
+
+    // A partial write succeeds, then the full buffer is an error
+    assert_eq!(4, writer.write(b"hello").unwrap());
+    let err = writer.write(b"o").unwrap_err();
+    assert_eq!(ErrorKind::Other, err.kind());
+    assert!(err.get_ref().unwrap().is::<BufferFull>());
+
+    // Empty writes always succeed
+    assert_eq!(0, writer.write(b"").unwrap());
+
+    writer.set_when_full(WhenFull::WouldBlock);
+    assert_eq!(ErrorKind::WouldBlock, writer.write(b"o").unwrap_err().kind());
+
+    writer.set_when_full(WhenFull::Error);
+    assert!(writer.write(b"o").unwrap_err().get_ref().unwrap().is::<BufferFull>());
+
+    let mut writer = Writer::new(SliceBuf::with_capacity(4));
+    let err = writer.write_all(b"hello").unwrap_err();
+    assert!(err.get_ref().unwrap().is::<BufferFull>());
+}
+
+/// A `MutBuf` that counts calls to `flush_buf`
+struct Flushed {
+    buf: Vec<u8>,
+    flushes: usize,
+}
+
+impl MutBuf for Flushed {
+    fn remaining(&self) -> usize {
+        self.buf.remaining()
+    }
+
+    unsafe fn advance(&mut self, cnt: usize) {
+        self.buf.advance(cnt)
+    }
+
+    fn mut_bytes(&mut self) -> &mut UninitSlice {
+        self.buf.mut_bytes()
+    }
+
+    fn flush_buf(&mut self) -> ::std::io::Result<()> {
+        self.flushes += 1;
+        Ok(())
+    }
+}
+
+#[test]
+pub fn test_writer_forwards_flush() {
+    use std::io::Write;
+
+    let inner = Flushed { buf: vec![], flushes: 0 };
+    let mut writer = Writer::new(inner.take(100));
+
+    writer.write_all(b"hello").unwrap();
+    writer.flush().unwrap();
+
+    let inner = writer.into_inner().into_inner();
+    assert_eq!(1, inner.flushes);
+    assert_eq!(inner.buf, b"hello");
+}
+
+#[test]
+pub fn test_fmt_overflow() {
+    use std::fmt::Write;
+
+    let mut buf = SliceBuf::with_capacity(8);
+
+    {
+        let mut fmt = TruncatingFmt::new(&mut buf);
+
+        assert!(write!(fmt, "{}", 1234).is_ok());
+        assert_eq!(0, fmt.truncated());
+
+        assert!(write!(fmt, "{}:{}", "abc", "defgh").is_err());
+        assert_eq!(5, fmt.truncated());
+
+        assert!(fmt.write_str("!").is_err());
+        assert_eq!(6, fmt.truncated());
+    }
+
+    assert_eq!(b"1234abc:", bytes::Buf::bytes(&buf));
+}
+
+#[test]
+pub fn test_write_fmt() {
+    let mut buf = SliceBuf::with_capacity(4);
+
+    assert!(write!(buf, "{}", 12).is_ok());
+    assert!(write!(buf, "{}", 345).is_err());
+
+    assert_eq!(b"1234", bytes::Buf::bytes(&buf));
+
+    let mut buf = Vec::new();
+    write!(buf, "Content-Length: {}\r\n", 1024).unwrap();
+
+    assert_eq!(buf, b"Content-Length: 1024\r\n");
+}
+
+#[test]
+pub fn test_fmt_tuple() {
+    use std::fmt::Write;
+
+    let mut buf = SliceBuf::with_capacity(4);
+
+    assert!(write!(Fmt(&mut buf), "{}", 12).is_ok());
+    assert!(write!(Fmt(&mut buf), "{}", 345).is_err());
+
+    assert_eq!(b"1234", bytes::Buf::bytes(&buf));
+}
+
+#[test]
+pub fn test_io_write_and_mut_buf_in_scope() {
+    // Only `FmtExt` provides a conflicting `write_fmt`
+    mod io_write {
+        use bytes::MutBuf;
+        use std::io::Write;
+
+        pub fn run() -> Vec<u8> {
+            let mut buf = vec![];
+            write!(buf, "{}", 1).unwrap();
+            buf.write_u8(b'2');
+            buf
+        }
+    }
+
+    assert_eq!(io_write::run(), b"12");
+}
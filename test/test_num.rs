@@ -0,0 +1,168 @@
+use bytes::{Buf, BufExt, MutBuf, MutBufExt};
+use bytes::buf::BlockBuf;
+use byteorder::{BigEndian, LittleEndian};
+use std::io::Cursor;
+use std::{f32, i128, u128};
+
+/// Write `src` into a `BlockBuf` with 3 byte blocks, offset by `skew` bytes
+/// so that each value straddles chunk boundaries at different positions.
+fn chunked(src: &[u8], skew: usize) -> BlockBuf {
+    let mut buf = BlockBuf::new((src.len() + skew) / 3 + 1, 3);
+    buf.put_bytes(0, skew);
+    buf.write_slice(src);
+    buf
+}
+
+#[test]
+pub fn test_u128() {
+    let n = 0x0102_0304_0506_0708_090A_0B0C_0D0E_0F10u128;
+
+    let mut buf = vec![];
+    buf.write_u128::<BigEndian>(n);
+    assert_eq!(buf, b"\x01\x02\x03\x04\x05\x06\x07\x08\x09\x0A\x0B\x0C\x0D\x0E\x0F\x10");
+    assert_eq!(n, Cursor::new(&buf).read_u128::<BigEndian>());
+
+    let mut buf = vec![];
+    buf.write_u128::<LittleEndian>(n);
+    assert_eq!(buf, b"\x10\x0F\x0E\x0D\x0C\x0B\x0A\x09\x08\x07\x06\x05\x04\x03\x02\x01");
+    assert_eq!(n, Cursor::new(&buf).read_u128::<LittleEndian>());
+}
+
+#[test]
+pub fn test_i128() {
+    for &n in &[0, -1, 1, i128::MIN, i128::MAX] {
+        let mut buf = vec![];
+        buf.write_i128::<LittleEndian>(n);
+        buf.write_i128::<BigEndian>(n);
+
+        let mut buf = Cursor::new(&buf);
+        assert_eq!(n, buf.read_i128::<LittleEndian>());
+        assert_eq!(n, buf.read_i128::<BigEndian>());
+    }
+}
+
+#[test]
+pub fn test_24_bit() {
+    let mut buf = vec![];
+    buf.write_u24::<BigEndian>(0x010203);
+    buf.write_u24::<LittleEndian>(0x010203);
+    buf.write_i24::<BigEndian>(-2);
+    buf.write_i24::<LittleEndian>(-(1 << 23));
+    assert_eq!(buf, b"\x01\x02\x03\x03\x02\x01\xFF\xFF\xFE\x00\x00\x80");
+
+    let mut buf = Cursor::new(&buf);
+    assert_eq!(0x010203, buf.read_u24::<BigEndian>());
+    assert_eq!(0x010203, buf.read_u24::<LittleEndian>());
+    assert_eq!(-2, buf.read_i24::<BigEndian>());
+    assert_eq!(-(1 << 23), buf.read_i24::<LittleEndian>());
+    assert!(!buf.has_remaining());
+}
+
+#[test]
+#[should_panic]
+pub fn test_u24_out_of_range() {
+    vec![].write_u24::<BigEndian>(1 << 24);
+}
+
+#[test]
+#[should_panic]
+pub fn test_i24_out_of_range() {
+    vec![].write_i24::<BigEndian>(1 << 23);
+}
+
+#[test]
+pub fn test_f16_known_values() {
+    let cases: &[(f32, u16)] = &[
+        (0.0, 0x0000),
+        (-0.0, 0x8000),
+        (1.0, 0x3C00),
+        (-2.0, 0xC000),
+        (0.5, 0x3800),
+        (65504.0, 0x7BFF),
+        (6.103515625e-5, 0x0400),
+        (5.9604645e-8, 0x0001),
+        (f32::INFINITY, 0x7C00),
+        (f32::NEG_INFINITY, 0xFC00),
+    ];
+
+    for &(n, bits) in cases {
+        let mut buf = vec![];
+        buf.write_f16::<BigEndian>(n);
+        assert_eq!(buf, [(bits >> 8) as u8, bits as u8]);
+
+        let val = Cursor::new(&buf).read_f16::<BigEndian>();
+        assert_eq!(val.to_bits(), n.to_bits());
+    }
+}
+
+#[test]
+pub fn test_f16_rounding() {
+    let cases: &[(f32, u16)] = &[
+        // 0.1 is between 0x2E66 and 0x2E67, closer to the former
+        (0.1, 0x2E66),
+        // Exactly halfway between 65504 and infinity rounds to even
+        (65520.0, 0x7C00),
+        (65519.0, 0x7BFF),
+        (1e10, 0x7C00),
+        // Halfway between 1.0 and the next float rounds down to even
+        (1.0 + 1.0 / 2048.0, 0x3C00),
+        // Halfway between 0x3C01 and 0x3C02 rounds up to even
+        (1.0 + 3.0 / 2048.0, 0x3C02),
+        // Half of the smallest subnormal rounds to zero, more rounds up
+        (2.9802322e-8, 0x0000),
+        (2.9802326e-8, 0x0001),
+        (1e-10, 0x0000),
+        // Rounding the largest subnormal up gives the smallest normal
+        (6.1e-5, 0x03FF),
+        (6.10351e-5, 0x0400),
+    ];
+
+    for &(n, bits) in cases {
+        let mut buf = vec![];
+        buf.write_f16::<LittleEndian>(n);
+        assert_eq!(bits, Cursor::new(&buf).read_u16::<LittleEndian>(), "{}", n);
+    }
+}
+
+#[test]
+pub fn test_f16_round_trip_all() {
+    for bits in 0..=0xFFFFu16 {
+        let mut buf = vec![];
+        buf.write_u16::<BigEndian>(bits);
+
+        let val = Cursor::new(&buf).read_f16::<BigEndian>();
+
+        let mut out = vec![];
+        out.write_f16::<BigEndian>(val);
+
+        if val.is_nan() {
+            assert_eq!(bits & 0x7C00, 0x7C00);
+            assert!(Cursor::new(&out).read_f16::<BigEndian>().is_nan());
+        } else {
+            assert_eq!(out, buf);
+        }
+    }
+}
+
+#[test]
+pub fn test_across_chunks() {
+    let mut src = vec![];
+    src.write_u128::<BigEndian>(u128::MAX - 1);
+    src.write_i24::<LittleEndian>(-1_000_000);
+    src.write_f16::<LittleEndian>(-1.5);
+    src.write_i128::<LittleEndian>(i128::MIN + 7);
+    src.write_u24::<BigEndian>(0xABCDEF);
+
+    for skew in 0..3 {
+        let block = chunked(&src, skew);
+        let mut buf = block.buf();
+        buf.advance(skew);
+
+        assert_eq!(u128::MAX - 1, buf.read_u128::<BigEndian>());
+        assert_eq!(-1_000_000, buf.read_i24::<LittleEndian>());
+        assert_eq!(-1.5, buf.read_f16::<LittleEndian>());
+        assert_eq!(i128::MIN + 7, buf.read_i128::<LittleEndian>());
+        assert_eq!(0xABCDEF, buf.read_u24::<BigEndian>());
+        assert!(!buf.has_remaining());
+    }
+}
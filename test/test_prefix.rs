@@ -0,0 +1,312 @@
+use bytes::{Buf, BufExt, MutBuf, Bytes};
+use bytes::buf::{AppendBuf, BlockBuf, RingBuf, SliceBuf, Patch, LengthPrefix, PrefixError};
+use std::io::Cursor;
+
+fn to_vec(bytes: &Bytes) -> Vec<u8> {
+    let mut vec = vec![];
+    bytes.buf().copy_to(&mut vec);
+    vec
+}
+
+#[test]
+pub fn test_fill_prefix_fixed_width() {
+    let cases: Vec<(LengthPrefix, &[u8])> = vec![
+        (LengthPrefix::U8, b"\x05"),
+        (LengthPrefix::U16BE, b"\x00\x05"),
+        (LengthPrefix::U16LE, b"\x05\x00"),
+        (LengthPrefix::U32BE, b"\x00\x00\x00\x05"),
+        (LengthPrefix::U32LE, b"\x05\x00\x00\x00"),
+        (LengthPrefix::Varint, b"\x85\x80\x80\x80\x00"),
+    ];
+
+    for (kind, prefix) in cases {
+        let mut buf = vec![];
+
+        buf.write_slice(b"<");
+        let mark = buf.reserve_prefix(kind);
+        buf.write_slice(b"hello");
+        assert_eq!(5, buf.fill_prefix(mark));
+        buf.write_slice(b">");
+
+        let mut expected = b"<".to_vec();
+        expected.extend_from_slice(prefix);
+        expected.extend_from_slice(b"hello>");
+
+        assert_eq!(buf, expected);
+    }
+}
+
+#[test]
+pub fn test_fill_prefix_varint_multi_byte() {
+    let mut buf = vec![];
+
+    let mark = buf.reserve_prefix(LengthPrefix::Varint);
+    buf.write_slice(&[0; 300]);
+    assert_eq!(300, buf.fill_prefix(mark));
+
+    assert_eq!(&buf[..5], b"\xAC\x82\x80\x80\x00");
+}
+
+#[test]
+pub fn test_nested_prefixes() {
+    let mut buf = SliceBuf::with_capacity(64);
+
+    let outer = buf.reserve_prefix(LengthPrefix::U16BE);
+    buf.write_slice(b"ab");
+
+    let inner = buf.reserve_prefix(LengthPrefix::U8);
+    buf.write_slice(b"cde");
+    assert_eq!(3, buf.fill_prefix(inner));
+
+    assert_eq!(6, buf.fill_prefix(outer));
+
+    assert_eq!(b"\x00\x06ab\x03cde", buf.bytes());
+}
+
+#[test]
+pub fn test_prefix_growable_slice_buf() {
+    let mut buf = SliceBuf::growable(4);
+
+    let mark = buf.reserve_prefix(LengthPrefix::U32LE);
+    buf.write_slice(&[7; 1_000]);
+    assert_eq!(1_000, buf.fill_prefix(mark));
+
+    assert_eq!(1_000, buf.read_u32::<::byteorder::LittleEndian>());
+    assert_eq!(1_000, Buf::remaining(&buf));
+}
+
+#[test]
+pub fn test_prefix_append_buf() {
+    let mut buf = AppendBuf::with_capacity(64);
+
+    buf.write_slice(b"xy");
+    let _ = buf.shift(2);
+
+    let mark = buf.reserve_prefix(LengthPrefix::U8);
+    buf.write_slice(b"hello");
+    buf.fill_prefix(mark);
+
+    assert_eq!(b"\x05hello", buf.bytes());
+}
+
+#[test]
+#[should_panic]
+pub fn test_prefix_overflow() {
+    let mut buf = vec![];
+
+    let mark = buf.reserve_prefix(LengthPrefix::U8);
+    buf.write_slice(&[0; 256]);
+    buf.fill_prefix(mark);
+}
+
+#[test]
+#[should_panic]
+pub fn test_patch_shifted_bytes() {
+    let mut buf = AppendBuf::with_capacity(64);
+
+    buf.write_slice(b"hello");
+    let _ = buf.shift(2);
+    buf.patch(0, b"j");
+}
+
+#[test]
+#[should_panic]
+pub fn test_patch_sliced_bytes() {
+    let mut buf = AppendBuf::with_capacity(64);
+
+    buf.write_slice(b"hello");
+    let _ = buf.slice(1, 3);
+    buf.patch(2, b"j");
+}
+
+#[test]
+pub fn test_patch_after_sliced_bytes() {
+    let mut buf = AppendBuf::with_capacity(64);
+
+    buf.write_slice(b"hello");
+    let _ = buf.slice(1, 3);
+    buf.patch(3, b"j");
+
+    assert_eq!(b"heljo", buf.bytes());
+}
+
+#[test]
+pub fn test_write_read_prefixed() {
+    let kinds = [LengthPrefix::U8, LengthPrefix::U16BE, LengthPrefix::U16LE,
+                 LengthPrefix::U32BE, LengthPrefix::U32LE, LengthPrefix::Varint];
+
+    for &kind in &kinds {
+        let mut buf = vec![];
+        buf.write_prefixed(kind, b"hello");
+        buf.write_prefixed(kind, b"");
+        buf.write_prefixed(kind, &[9; 200]);
+
+        let mut buf = Cursor::new(buf);
+        assert_eq!(b"hello", &to_vec(&buf.read_prefixed_bytes(kind, 1024).unwrap())[..]);
+        assert_eq!(0, buf.read_prefixed_bytes(kind, 1024).unwrap().len());
+        assert_eq!(vec![9; 200], to_vec(&buf.read_prefixed_bytes(kind, 1024).unwrap()));
+        assert!(!Buf::has_remaining(&buf));
+    }
+}
+
+#[test]
+pub fn test_write_prefixed_varint_is_minimal() {
+    let mut buf = vec![];
+    buf.write_prefixed(LengthPrefix::Varint, &[0; 300]);
+    assert_eq!(&buf[..2], b"\xAC\x02");
+    assert_eq!(buf.len(), 302);
+}
+
+#[test]
+pub fn test_read_prefixed_padded_varint() {
+    let mut buf = vec![];
+    let mark = buf.reserve_prefix(LengthPrefix::Varint);
+    buf.write_slice(b"abc");
+    buf.fill_prefix(mark);
+
+    let mut buf = Cursor::new(buf);
+    assert_eq!(b"abc", &to_vec(&buf.read_prefixed_bytes(LengthPrefix::Varint, 1024).unwrap())[..]);
+}
+
+#[test]
+pub fn test_read_prefixed_zero_copy() {
+    let src = Bytes::from(&b"\x00\x05hello world"[..]);
+    let mut buf = src.buf();
+
+    let hello = buf.read_prefixed_bytes(LengthPrefix::U16BE, 1024).unwrap();
+    assert_eq!(b"hello", &to_vec(&hello)[..]);
+    assert_eq!(hello.buf().bytes().as_ptr(), src.buf().bytes()[2..].as_ptr());
+    assert_eq!(b" world", buf.bytes());
+}
+
+#[test]
+pub fn test_read_prefixed_across_chunks() {
+    let mut src = vec![];
+    src.write_prefixed(LengthPrefix::U32LE, b"0123456789");
+    src.write_slice(b"!");
+
+    let mut block = BlockBuf::new(8, 3);
+    block.write_slice(&src);
+
+    let mut buf = block.buf();
+    let bytes = buf.read_prefixed_bytes(LengthPrefix::U32LE, 1024).unwrap();
+    assert_eq!(b"0123456789", &to_vec(&bytes)[..]);
+    assert_eq!(b"!", buf.bytes());
+
+    let rope = Bytes::from(&src[..7]).concat(&Bytes::from(&src[7..]));
+    let mut buf = rope.buf();
+    let bytes = buf.read_prefixed_bytes(LengthPrefix::U32LE, 1024).unwrap();
+    assert_eq!(b"0123456789", &to_vec(&bytes)[..]);
+    assert_eq!(1, buf.remaining());
+}
+
+#[test]
+pub fn test_read_prefixed_errors() {
+    // Truncated prefix
+    let mut buf = Cursor::new(b"\x00");
+    assert_eq!(Err(PrefixError::Incomplete), buf.read_prefixed_bytes(LengthPrefix::U16BE, 1024));
+    assert_eq!(1, buf.remaining());
+
+    // Truncated payload
+    let mut buf = Cursor::new(b"\x05abc");
+    assert_eq!(Err(PrefixError::Incomplete), buf.read_prefixed_bytes(LengthPrefix::U8, 1024));
+    assert_eq!(b"\x05abc", buf.bytes());
+
+    // Varints longer than 5 bytes or larger than a u32
+    let mut buf = Cursor::new(b"\x80\x80\x80\x80\x80\x00");
+    assert_eq!(Err(PrefixError::InvalidVarint), buf.read_prefixed_bytes(LengthPrefix::Varint, 1024));
+
+    let mut buf = Cursor::new(b"\xFF\xFF\xFF\xFF\x1F");
+    assert_eq!(Err(PrefixError::InvalidVarint), buf.read_prefixed_bytes(LengthPrefix::Varint, 1024));
+
+    let mut buf = Cursor::new(b"\x80");
+    assert_eq!(Err(PrefixError::Incomplete), buf.read_prefixed_bytes(LengthPrefix::Varint, 1024));
+    assert_eq!(1, buf.remaining());
+}
+
+#[test]
+pub fn test_read_prefixed_bytes_too_long() {
+    let mut src = vec![];
+    src.write_prefixed(LengthPrefix::U32LE, &[7; 100]);
+
+    let mut buf = Cursor::new(src);
+    assert_eq!(Err(PrefixError::TooLong), buf.read_prefixed_bytes(LengthPrefix::U32LE, 99));
+    assert_eq!(104, Buf::remaining(&buf));
+    assert_eq!(100, buf.read_prefixed_bytes(LengthPrefix::U32LE, 100).unwrap().len());
+}
+
+#[test]
+pub fn test_read_prefixed_retry() {
+    let mut src = vec![];
+    src.write_prefixed(LengthPrefix::U32BE, b"hello");
+
+    // The prefix wraps around the end of the ring
+    let mut buf = RingBuf::with_capacity(16);
+    buf.write_slice(&[0; 14]);
+    Buf::advance(&mut buf, 14);
+
+    for i in 0..src.len() {
+        buf.write_slice(&src[i..i + 1]);
+
+        if i + 1 < src.len() {
+            assert_eq!(Err(PrefixError::Incomplete), buf.read_prefixed_bytes(LengthPrefix::U32BE, 1024));
+            assert_eq!(i + 1, Buf::remaining(&buf));
+        }
+    }
+
+    let bytes = buf.read_prefixed_bytes(LengthPrefix::U32BE, 1024).unwrap();
+    assert_eq!(b"hello", &to_vec(&bytes)[..]);
+}
+
+#[test]
+pub fn test_peek_slice_across_chunks() {
+    let mut block = BlockBuf::new(8, 3);
+    block.write_slice(b"abcdefgh");
+
+    let mut buf = block.buf();
+    buf.advance(2);
+
+    let mut dst = [0; 5];
+    assert_eq!(5, buf.peek_slice(&mut dst));
+    assert_eq!(b"cdefg", &dst);
+    assert_eq!(6, buf.remaining());
+
+    let mut dst = [0; 8];
+    assert_eq!(6, buf.peek_slice(&mut dst));
+    assert_eq!(b"cdefgh", &dst[..6]);
+
+    let rope = Bytes::from(&[b'x'; 200][..]).concat(&Bytes::from(&[b'y'; 200][..]));
+    let mut buf = rope.buf();
+    buf.advance(198);
+
+    let mut dst = [0; 4];
+    assert_eq!(4, buf.peek_slice(&mut dst));
+    assert_eq!(b"xxyy", &dst);
+
+    let mut dst = [0; 4];
+    assert_eq!(2, buf.by_ref().take(2).peek_slice(&mut dst));
+    assert_eq!(b"xx", &dst[..2]);
+}
+
+#[test]
+pub fn test_read_prefixed_str() {
+    let mut src = vec![];
+    src.write_prefixed(LengthPrefix::U8, "héllo".as_bytes());
+    src.write_prefixed(LengthPrefix::U8, b"\xFF\xFE");
+    src.write_prefixed(LengthPrefix::U8, b"this is too long");
+    src.write_slice(b"!");
+
+    let mut buf = Cursor::new(src);
+    assert_eq!(Ok("héllo".to_string()), buf.read_prefixed_str(LengthPrefix::U8, 8));
+    assert_eq!(Err(PrefixError::InvalidUtf8), buf.read_prefixed_str(LengthPrefix::U8, 8));
+
+    // A value that is too long is left in the buffer
+    assert_eq!(Err(PrefixError::TooLong), buf.read_prefixed_str(LengthPrefix::U8, 8));
+    assert_eq!(18, Buf::remaining(&buf));
+}
+
+#[test]
+#[should_panic]
+pub fn test_write_prefixed_too_long() {
+    vec![].write_prefixed(LengthPrefix::U8, &[0; 256]);
+}
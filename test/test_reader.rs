@@ -0,0 +1,199 @@
+use bytes::{Buf, BufExt, MutBuf, Bytes, BytesBuilder};
+use bytes::buf::{BlockBuf, SeekBuf, SliceBuf};
+use std::io::{self, BufRead, Cursor, Read, Seek, SeekFrom};
+
+#[test]
+pub fn test_read_line_across_chunks() {
+    let mut block = BlockBuf::new(16, 3);
+    block.write_slice(b"first line\nsecond\n\nlast");
+
+    let mut reader = block.buf().reader();
+    let mut line = String::new();
+
+    assert_eq!(11, reader.read_line(&mut line).unwrap());
+    assert_eq!("first line\n", line);
+
+    let lines: Vec<String> = reader.lines().map(|l| l.unwrap()).collect();
+    assert_eq!(lines, ["second", "", "last"]);
+}
+
+#[test]
+pub fn test_split_rope() {
+    let rope = Bytes::from(&b"a,bc"[..]).concat(&Bytes::from(&b",,def"[..]));
+
+    let parts: Vec<Vec<u8>> = rope.buf().reader().split(b',').map(|p| p.unwrap()).collect();
+    assert_eq!(parts, [&b"a"[..], b"bc", b"", b"def"]);
+}
+
+#[test]
+pub fn test_fill_buf_and_consume() {
+    // Pieces longer than a rope leaf's copy threshold stay separate chunks
+    let left = vec![b'a'; 200];
+    let right = vec![b'b'; 200];
+    let rope = Bytes::from(&left[..]).concat(&Bytes::from(&right[..]));
+
+    let mut reader = rope.buf().reader();
+
+    assert_eq!(&left[..], reader.fill_buf().unwrap());
+    reader.consume(150);
+    assert_eq!(&left[150..], reader.fill_buf().unwrap());
+    reader.consume(50);
+    assert_eq!(&right[..], reader.fill_buf().unwrap());
+    reader.consume(200);
+    assert!(reader.fill_buf().unwrap().is_empty());
+}
+
+#[test]
+pub fn test_seek_cursor() {
+    let mut reader = Cursor::new(b"hello world").reader();
+
+    assert_eq!(6, reader.seek(SeekFrom::Start(6)).unwrap());
+
+    let mut dst = String::new();
+    reader.read_to_string(&mut dst).unwrap();
+    assert_eq!("world", dst);
+
+    assert_eq!(6, reader.seek(SeekFrom::End(-5)).unwrap());
+    assert_eq!(4, reader.seek(SeekFrom::Current(-2)).unwrap());
+    assert_eq!(b"o world", reader.fill_buf().unwrap());
+
+    assert_eq!(11, reader.seek(SeekFrom::End(0)).unwrap());
+}
+
+#[test]
+pub fn test_seek_slice_buf() {
+    let mut buf = SliceBuf::with_capacity(16);
+    buf.write_slice(b"0123456789");
+
+    let mut reader = buf.reader();
+    reader.consume(5);
+
+    assert_eq!(8, reader.seek(SeekFrom::Current(3)).unwrap());
+    assert_eq!(b"89", reader.fill_buf().unwrap());
+    assert_eq!(0, reader.seek(SeekFrom::Start(0)).unwrap());
+    assert_eq!(10, Buf::remaining(reader.get_ref()));
+}
+
+#[test]
+pub fn test_seek_out_of_bounds() {
+    let mut reader = Cursor::new(b"hello").reader();
+    reader.consume(2);
+
+    for &pos in &[SeekFrom::Start(6), SeekFrom::End(1), SeekFrom::Current(-3)] {
+        let err = reader.seek(pos).unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidInput, err.kind());
+    }
+
+    // Failed seeks leave the position unchanged
+    assert_eq!(2, reader.seek(SeekFrom::Current(0)).unwrap());
+}
+
+/// A rope of 200 byte pieces, long enough that they are not flattened
+fn rope(src: &[u8]) -> Bytes {
+    let mut builder = BytesBuilder::new();
+
+    for piece in src.chunks(200) {
+        builder.push(Bytes::from(piece));
+    }
+
+    builder.build()
+}
+
+#[test]
+pub fn test_rope_set_position() {
+    let src: Vec<u8> = (0..5000).map(|i| (i % 251) as u8).collect();
+    let bytes = rope(&src);
+    let mut buf = bytes.buf();
+
+    for &pos in &[0, 1, 199, 200, 201, 2500, 4999, 5000, 3, 4800] {
+        buf.set_position(pos);
+
+        assert_eq!(pos, buf.position());
+        assert_eq!(5000 - pos, buf.remaining());
+
+        let mut dst = vec![];
+        buf.copy_to(&mut dst);
+        assert_eq!(&dst[..], &src[pos..]);
+        assert_eq!(5000, buf.position());
+    }
+}
+
+#[test]
+pub fn test_rope_position_after_advance() {
+    let src: Vec<u8> = (0..1000).map(|i| (i % 251) as u8).collect();
+    let bytes = rope(&src);
+    let mut buf = bytes.buf();
+
+    buf.advance(450);
+    assert_eq!(450, buf.position());
+
+    buf.set_position(10);
+    let n = buf.bytes().len();
+    assert!(n > 0);
+    assert_eq!(&src[10..10 + n], buf.bytes());
+    buf.advance(300);
+    assert_eq!(310, buf.position());
+    assert_eq!(src[310], buf.read_u8());
+}
+
+#[test]
+pub fn test_block_cursor_set_position() {
+    let src: Vec<u8> = (0..40).collect();
+    let mut block = BlockBuf::new(16, 3);
+    block.write_slice(&src);
+    block.drop(2);
+
+    let mut buf = block.buf();
+
+    for pos in (0..38).rev() {
+        buf.set_position(pos);
+
+        assert_eq!(pos, buf.position());
+        assert_eq!(src[pos + 2], buf.read_u8());
+    }
+
+    buf.set_position(38);
+    assert!(!buf.has_remaining());
+}
+
+#[test]
+#[should_panic]
+pub fn test_block_cursor_set_position_out_of_bounds() {
+    let mut block = BlockBuf::new(2, 4);
+    block.write_slice(b"abc");
+
+    block.buf().set_position(4);
+}
+
+#[test]
+pub fn test_seek_rope() {
+    let src: Vec<u8> = (0..1000).map(|i| (i % 251) as u8).collect();
+    let bytes = rope(&src);
+    let mut reader = bytes.buf().reader();
+
+    assert_eq!(990, reader.seek(SeekFrom::End(-10)).unwrap());
+
+    let mut dst = vec![];
+    reader.read_to_end(&mut dst).unwrap();
+    assert_eq!(&dst[..], &src[990..]);
+
+    assert_eq!(400, reader.seek(SeekFrom::Start(400)).unwrap());
+    let chunk = reader.fill_buf().unwrap();
+    assert!(!chunk.is_empty());
+    assert_eq!(&src[400..400 + chunk.len()], chunk);
+}
+
+#[test]
+pub fn test_seek_block_cursor() {
+    let mut block = BlockBuf::new(16, 3);
+    block.write_slice(b"hello world");
+
+    let mut reader = block.buf().reader();
+    reader.consume(8);
+
+    assert_eq!(2, reader.seek(SeekFrom::Current(-6)).unwrap());
+
+    let mut dst = String::new();
+    reader.read_to_string(&mut dst).unwrap();
+    assert_eq!("llo world", dst);
+}
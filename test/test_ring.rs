@@ -1,4 +1,4 @@
-use bytes::{Buf, MutBuf};
+use bytes::{Buf, BufExt, MutBuf, MutBufExt};
 use bytes::buf::RingBuf;
 
 #[test]
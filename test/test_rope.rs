@@ -1,4 +1,4 @@
-use bytes::{Buf, Bytes};
+use bytes::{Buf, BufExt, Bytes, BytesBuilder};
 
 const TEST_BYTES_1: &'static [u8] =
     b"dblm4ng7jp4v9rdn1w6hhssmluoqrrrqj59rccl9
@@ -81,3 +81,207 @@ pub fn test_rope_equality() {
 
     assert_eq!(a, b);
 }
+
+#[test]
+pub fn test_rope_from_iter() {
+    let pieces: Vec<Bytes> = TEST_BYTES_1.chunks(10)
+        .chain(TEST_BYTES_2.chunks(33))
+        .map(Bytes::from)
+        .collect();
+
+    let bytes: Bytes = pieces.iter().cloned().collect();
+
+    let mut expected = Vec::new();
+    expected.extend(TEST_BYTES_1.iter().cloned());
+    expected.extend(TEST_BYTES_2.iter().cloned());
+
+    let mut dst = vec![];
+    bytes.buf().copy_to(&mut dst);
+    assert_eq!(dst, expected);
+
+    for i in 0..expected.len() {
+        assert_eq!(expected[i], bytes[i]);
+    }
+
+    dst.clear();
+    Bytes::concat_all(&pieces).buf().copy_to(&mut dst);
+    assert_eq!(dst, expected);
+}
+
+#[test]
+pub fn test_rope_from_iter_empty_pieces() {
+    let pieces = vec![Bytes::empty(), Bytes::from(TEST_BYTES_1), Bytes::empty()];
+
+    let bytes = Bytes::concat_all(&pieces);
+    assert_eq!(TEST_BYTES_1.len(), bytes.len());
+
+    let mut dst = vec![];
+    bytes.buf().copy_to(&mut dst);
+    assert_eq!(dst, TEST_BYTES_1);
+
+    assert!(Bytes::concat_all(&[]).is_empty());
+    assert!(Bytes::concat_all(&[Bytes::empty(), Bytes::empty()]).is_empty());
+}
+
+#[test]
+pub fn test_rope_builder() {
+    let mut builder = BytesBuilder::new();
+
+    builder.extend(TEST_BYTES_1[..100].iter());
+    builder.push(Bytes::from(&TEST_BYTES_1[100..]));
+    builder.extend(vec![Bytes::from(TEST_BYTES_2)]);
+    builder.extend(b"!!".iter().cloned());
+
+    assert_eq!(TEST_BYTES_1.len() + TEST_BYTES_2.len() + 2, builder.len());
+
+    let bytes = builder.build();
+
+    let mut expected = Vec::new();
+    expected.extend(TEST_BYTES_1.iter().cloned());
+    expected.extend(TEST_BYTES_2.iter().cloned());
+    expected.extend(b"!!".iter().cloned());
+
+    let mut dst = vec![];
+    bytes.buf().copy_to(&mut dst);
+    assert_eq!(dst, expected);
+}
+
+#[test]
+pub fn test_rope_deep_concat_and_drop() {
+    const N: usize = 1_000_000;
+
+    let mut bytes = Bytes::empty();
+
+    for i in 0..N {
+        bytes = bytes.concat(&Bytes::from_slice(&[i as u8]));
+    }
+
+    assert_eq!(N, bytes.len());
+
+    for &i in &[0, 1, 127, 128, 4_095, 500_000, N - 1] {
+        assert_eq!(i as u8, bytes[i]);
+    }
+
+    let slice = bytes.slice(1_000, N - 1_000);
+    assert_eq!(N - 2_000, slice.len());
+
+    let mut buf = slice.buf();
+    let mut i = 1_000;
+
+    while buf.has_remaining() {
+        assert_eq!(i as u8, buf.read_u8());
+        i += 1;
+    }
+
+    assert_eq!(N - 1_000, i);
+
+    drop(bytes);
+    assert_eq!(0xE8, slice[0]);
+}
+
+#[test]
+pub fn test_rope_unbalanced_chain_and_drop() {
+    // Prepending leaves builds a chain leaning to the right, one level per
+    // leaf. Large leaves keep the rope long enough for its depth that it is
+    // not rebalanced. The leaves share their memory.
+    const N: usize = 32;
+    const LEAF_LEN: usize = 1 << 20;
+
+    let leaf = Bytes::from_slice(&vec![0; LEAF_LEN]);
+    let mut bytes = Bytes::from_slice(&vec![1; LEAF_LEN]);
+
+    for _ in 0..N {
+        bytes = leaf.concat(&bytes);
+    }
+
+    assert_eq!((N + 1) * LEAF_LEN, bytes.len());
+    assert_eq!(0, bytes[0]);
+    assert_eq!(1, bytes[N * LEAF_LEN]);
+
+    // Walk the chunks down the chain
+    let mut buf = bytes.buf();
+    let mut chunks = 0;
+
+    while buf.has_remaining() {
+        let n = buf.bytes().len();
+        buf.advance(n);
+        chunks += 1;
+    }
+
+    assert_eq!(N + 1, chunks);
+
+    let slice = bytes.slice_from(N * LEAF_LEN - 1);
+    assert_eq!(0, slice[0]);
+    assert_eq!(1, slice[1]);
+
+    drop(bytes);
+    assert_eq!(LEAF_LEN + 1, slice.len());
+    assert_eq!(1, slice[LEAF_LEN]);
+}
+
+// Builds a rope of `1 << (20 + doublings)` bytes sharing a single 1 MiB leaf
+#[cfg(target_pointer_width = "64")]
+fn sparse_rope(doublings: usize) -> Bytes {
+    let leaf: Vec<u8> = (0..1 << 20).map(|i: usize| (i % 251) as u8).collect();
+    let mut bytes = Bytes::from(leaf);
+
+    for _ in 0..doublings {
+        bytes = bytes.concat(&bytes);
+    }
+
+    bytes
+}
+
+#[cfg(target_pointer_width = "64")]
+fn sparse_byte(i: usize) -> u8 {
+    ((i % (1 << 20)) % 251) as u8
+}
+
+#[test]
+#[cfg(target_pointer_width = "64")]
+pub fn test_rope_len_over_4gb() {
+    let bytes = sparse_rope(13);
+    assert_eq!(8 << 30, bytes.len());
+
+    for &i in &[0, (4 << 30) - 1, 4 << 30, (4 << 30) + 12_345, (8 << 30) - 1] {
+        assert_eq!(sparse_byte(i), bytes[i]);
+    }
+
+    // Slice across the 4 GiB boundary
+    let slice = bytes.slice((4 << 30) - 100, (4 << 30) + 100);
+    assert_eq!(200, slice.len());
+
+    let mut buf = slice.buf();
+
+    for i in 0..200 {
+        assert_eq!(sparse_byte((4 << 30) - 100 + i), buf.read_u8());
+    }
+
+    let tail = bytes.slice_from((6 << 30) + 7);
+    assert_eq!((2 << 30) - 7, tail.len());
+    assert_eq!(sparse_byte((6 << 30) + 7), tail[0]);
+}
+
+#[test]
+#[cfg(target_pointer_width = "64")]
+pub fn test_rope_balance_over_4gb() {
+    let gb = sparse_rope(10);
+    let pieces = vec![gb.clone(), sparse_rope(13), gb.clone(), gb.clone()];
+
+    let bytes = Bytes::concat_all(&pieces);
+    assert_eq!(11 << 30, bytes.len());
+
+    for &i in &[0, (4 << 30) + 99, (9 << 30) + 1, (11 << 30) - 1] {
+        assert_eq!(sparse_byte(i), bytes[i]);
+    }
+
+    // Concatenating unbalanced pieces rebalances ropes over 4 GiB
+    let mut bytes = bytes;
+
+    for i in 0..64 {
+        bytes = bytes.concat(&Bytes::from(vec![i as u8; 200]));
+    }
+
+    assert_eq!((11 << 30) + 64 * 200, bytes.len());
+    assert_eq!(63, bytes[bytes.len() - 1]);
+}
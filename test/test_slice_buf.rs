@@ -1,4 +1,4 @@
-use bytes::{Buf, MutBuf};
+use bytes::{Buf, BufExt, MutBufExt};
 use bytes::buf::SliceBuf;
 
 #[test]
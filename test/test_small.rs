@@ -1,4 +1,4 @@
-use bytes::{Buf, Bytes};
+use bytes::{BufExt, Bytes};
 use super::gen_bytes;
 
 #[test]
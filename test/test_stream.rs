@@ -0,0 +1,305 @@
+use bytes::{Buf, BufExt, MutBuf, MutBufExt};
+use bytes::buf::{ReadBuf, WriteBuf};
+use byteorder::BigEndian;
+use std::{cmp, io};
+use std::io::Write;
+
+#[test]
+pub fn test_read_buf_parse_frames() {
+    let mut src = vec![];
+
+    for i in 0..100u32 {
+        src.extend_from_slice(&[0, 0, 0, (i % 7) as u8]);
+        src.extend((0..i % 7).map(|_| i as u8));
+    }
+
+    let mut buf = ReadBuf::with_capacity(8, Trickle::new(&src, 3));
+    let mut i = 0;
+
+    while buf.fill(4).unwrap() > 0 {
+        let len = buf.read_u32::<BigEndian>() as usize;
+        assert_eq!(len, (i % 7) as usize);
+
+        assert!(buf.fill(len).unwrap() >= len);
+        assert!(buf.bytes()[..len].iter().all(|&b| b == i as u8));
+        buf.advance(len);
+
+        i += 1;
+    }
+
+    assert_eq!(i, 100);
+}
+
+#[test]
+pub fn test_read_buf_fill_grows() {
+    let src: Vec<u8> = (0..100).collect();
+    let mut buf = ReadBuf::with_capacity(16, Trickle::new(&src, 7));
+
+    assert_eq!(7, buf.fill(1).unwrap());
+    buf.advance(5);
+
+    assert!(buf.fill(60).unwrap() >= 60);
+    assert!(buf.capacity() >= 60);
+    assert_eq!(&buf.bytes()[..60], &src[5..65]);
+}
+
+#[test]
+pub fn test_read_buf_fill_eof() {
+    let mut buf = ReadBuf::new(Trickle::new(b"abc", 2));
+
+    assert_eq!(3, buf.fill(8).unwrap());
+    assert_eq!(buf.bytes(), b"abc");
+
+    buf.advance(3);
+    assert_eq!(0, buf.fill(1).unwrap());
+}
+
+#[test]
+pub fn test_read_buf_fill_retries_interrupted() {
+    let mut src = Trickle::new(b"abcdef", 2);
+    src.interrupt = true;
+
+    let mut buf = ReadBuf::new(src);
+
+    assert_eq!(6, buf.fill(6).unwrap());
+    assert_eq!(buf.bytes(), b"abcdef");
+}
+
+#[test]
+#[should_panic]
+pub fn test_read_buf_advance_past_buffered() {
+    let mut buf = ReadBuf::new(&b"abc"[..]);
+
+    buf.fill(2).unwrap();
+    buf.advance(4);
+}
+
+#[test]
+pub fn test_read_buf_reads_only_buffered_bytes() {
+    let mut buf = ReadBuf::new(Trickle::new(b"\x00\x00\x00\x05hello", 2));
+
+    // The source has more data, but only `fill` reads it
+    assert_eq!(2, buf.fill(2).unwrap());
+    assert_eq!(2, buf.remaining());
+
+    assert_eq!(4, buf.fill(4).unwrap());
+    assert_eq!(5, buf.read_u32::<BigEndian>());
+    assert_eq!(0, buf.remaining());
+}
+
+#[test]
+#[should_panic]
+pub fn test_read_buf_read_without_fill() {
+    let mut buf = ReadBuf::new(Trickle::new(b"\x00\x00\x00\x05hello", 2));
+
+    buf.fill(2).unwrap();
+
+    // Panics: only 2 bytes are buffered even though the source has more
+    buf.read_u32::<BigEndian>();
+}
+
+#[test]
+pub fn test_read_buf_fill_compacts() {
+    let src: Vec<u8> = (0..24).collect();
+    let mut buf = ReadBuf::with_capacity(16, Trickle::new(&src, 5));
+
+    assert!(buf.fill(12).unwrap() >= 12);
+    buf.advance(10);
+
+    // The unread bytes move to the start instead of growing the buffer
+    assert!(buf.fill(12).unwrap() >= 12);
+    assert_eq!(16, buf.capacity());
+    assert_eq!(&buf.bytes()[..12], &src[10..22]);
+}
+
+/// Returns at most `max` bytes per read, interrupting every other read if
+/// `interrupt` is set
+struct Trickle<'a> {
+    src: &'a [u8],
+    max: usize,
+    interrupt: bool,
+    interrupted: bool,
+}
+
+impl<'a> Trickle<'a> {
+    fn new(src: &'a [u8], max: usize) -> Trickle<'a> {
+        Trickle {
+            src: src,
+            max: max,
+            interrupt: false,
+            interrupted: false,
+        }
+    }
+}
+
+impl<'a> io::Read for Trickle<'a> {
+    fn read(&mut self, dst: &mut [u8]) -> io::Result<usize> {
+        if self.interrupt && !self.interrupted {
+            self.interrupted = true;
+            return Err(io::Error::new(io::ErrorKind::Interrupted, "interrupted"));
+        }
+
+        self.interrupted = false;
+
+        let len = cmp::min(cmp::min(self.max, dst.len()), self.src.len());
+        dst[..len].copy_from_slice(&self.src[..len]);
+        self.src = &self.src[len..];
+
+        Ok(len)
+    }
+}
+
+#[test]
+pub fn test_write_buf_flushes_when_full() {
+    let mut buf = WriteBuf::with_capacity(4, Sink::default());
+
+    buf.write_slice(b"abc");
+    assert_eq!(0, buf.get_ref().data.len());
+
+    buf.write_u16::<BigEndian>(0x6465);
+    assert_eq!(buf.get_ref().data, b"abc");
+    assert_eq!(buf.buffered(), b"de");
+
+    buf.flush().unwrap();
+    assert_eq!(buf.get_ref().data, b"abcde");
+    assert_eq!(1, buf.get_ref().flushes);
+
+    let sink = buf.into_inner().unwrap();
+    assert_eq!(sink.data, b"abcde");
+    assert_eq!(2, sink.flushes);
+}
+
+#[test]
+pub fn test_write_buf_large_slices_are_vectored() {
+    let src: Vec<u8> = (0..100).collect();
+    let mut buf = WriteBuf::with_capacity(16, Sink::default());
+
+    buf.write_slice(b"head");
+    buf.write_slice(&src);
+
+    // The buffered bytes and the slice go out in one call
+    assert_eq!(1, buf.get_ref().vectored);
+    assert_eq!(0, buf.buffered().len());
+
+    let sink = buf.into_inner().unwrap();
+    assert_eq!(&sink.data[..4], b"head");
+    assert_eq!(&sink.data[4..], &src[..]);
+}
+
+#[test]
+pub fn test_write_buf_short_and_interrupted_writes() {
+    let mut sink = Sink::default();
+    sink.max = 3;
+    sink.interrupt = true;
+
+    let mut buf = WriteBuf::with_capacity(8, sink);
+    let src: Vec<u8> = (0..50).collect();
+
+    for chunk in src.chunks(5) {
+        buf.write_slice(chunk);
+    }
+
+    buf.write_slice(&src);
+
+    let mut expect = src.clone();
+    expect.extend_from_slice(&src);
+    assert_eq!(buf.into_inner().unwrap().data, expect);
+}
+
+#[test]
+pub fn test_write_buf_reports_errors() {
+    let mut sink = Sink::default();
+    sink.fail = true;
+
+    let mut buf = WriteBuf::with_capacity(4, sink);
+
+    // Writes never panic, even though the sink fails
+    buf.write_slice(b"abcdefgh");
+    buf.write_u32::<BigEndian>(1);
+    buf.write_slice(b"ij");
+
+    let err = buf.flush().unwrap_err();
+    assert_eq!(io::ErrorKind::BrokenPipe, err.kind());
+
+    buf.get_mut().fail = false;
+    buf.write_slice(b"kl");
+
+    assert_eq!(buf.into_inner().unwrap().data, b"kl");
+}
+
+#[test]
+pub fn test_write_buf_flushes_on_drop() {
+    let mut out = vec![];
+
+    {
+        let mut buf = WriteBuf::new(&mut out);
+        buf.write_slice(b"hello");
+    }
+
+    assert_eq!(out, b"hello");
+}
+
+#[test]
+pub fn test_write_buf_writer_flush() {
+    let mut writer = WriteBuf::with_capacity(4, Sink::default()).writer();
+
+    writer.write_all(b"hello world").unwrap();
+    writer.flush().unwrap();
+
+    let sink = writer.into_inner().into_inner().unwrap();
+    assert_eq!(sink.data, b"hello world");
+}
+
+/// An `io::Write` sink that records what is written to it
+#[derive(Debug, Default)]
+struct Sink {
+    data: Vec<u8>,
+    flushes: usize,
+    vectored: usize,
+    // Maximum number of bytes accepted per write, unlimited if 0
+    max: usize,
+    // Interrupt every other write
+    interrupt: bool,
+    interrupted: bool,
+    fail: bool,
+}
+
+impl io::Write for Sink {
+    fn write(&mut self, src: &[u8]) -> io::Result<usize> {
+        self.write_vectored(&[io::IoSlice::new(src)])
+    }
+
+    fn write_vectored(&mut self, bufs: &[io::IoSlice]) -> io::Result<usize> {
+        if self.fail {
+            return Err(io::Error::new(io::ErrorKind::BrokenPipe, "broken pipe"));
+        }
+
+        if self.interrupt && !self.interrupted {
+            self.interrupted = true;
+            return Err(io::Error::new(io::ErrorKind::Interrupted, "interrupted"));
+        }
+
+        self.interrupted = false;
+        self.vectored += 1;
+
+        let mut n = 0;
+
+        for buf in bufs {
+            let len = if self.max == 0 {
+                buf.len()
+            } else {
+                cmp::min(buf.len(), self.max - n)
+            };
+
+            self.data.extend_from_slice(&buf[..len]);
+            n += len;
+        }
+
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.flushes += 1;
+        Ok(())
+    }
+}